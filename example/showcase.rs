@@ -1,7 +1,9 @@
 #![allow(non_snake_case)]
 
 use dioxus::prelude::*;
-use dioxus_chessboard::{Action, BoardAction, Chessboard, ChessboardProps, Color, PieceSet};
+use dioxus_chessboard::{
+    use_chessboard, Action, BoardAction, Chessboard, ChessboardProps, Color, PieceSet,
+};
 use tracing::{debug, Level};
 
 #[cfg(feature = "showcase")]
@@ -26,7 +28,8 @@ fn App() -> Element {
     let mut single_player_mode = use_signal(|| false);
     let mut pieces_set = use_signal(|| PieceSet::Standard);
     let mut is_interactive = use_signal(|| true);
-    let mut action = use_signal(|| None);
+    let mut editor_mode = use_signal(|| false);
+    let chessboard = use_chessboard();
     let mut san_content = use_signal(|| "".to_string());
 
     let castling =
@@ -34,6 +37,8 @@ fn App() -> Element {
 
     let promotion = "rnbqkb1r/ppppn1P1/7p/8/8/4BN2/PPp1BPPP/RN1QK2R w KQkq - 2 9".to_string();
 
+    let puzzle = "6k1/5ppp/8/8/8/8/5PPP/4R1K1 w - - 0 1".to_string();
+
     let san_tx = use_coroutine(|mut rx: UnboundedReceiver<BoardAction>| async move {
         while let Some(msg) = rx.next().await {
             debug!("Chessboard reports: {msg}");
@@ -52,7 +57,8 @@ fn App() -> Element {
                     player_color: player_color.read().to_owned(),
                     single_player_mode: single_player_mode.read().to_owned(),
                     pieces_set: pieces_set.read().to_owned(),
-                    action: action.read().to_owned(),
+                    editor_mode: editor_mode.read().to_owned(),
+                    controller: chessboard,
                     san_tx,
                 }
             }
@@ -118,6 +124,36 @@ fn App() -> Element {
                     }
                 }
 
+                // Editor mode Radio Input
+                div { class: "space-y-2 border border-gray-300 rounded-lg p-2",
+                    label { class: "block text-gray-700 font-semibold", "Editor mode" }
+                    span { class: "text-xs text-gray-500", "(Right click removes a piece)" }
+                    div { class: "flex items-center space-x-4",
+                        label { class: "inline-flex items-center",
+                            input {
+                                r#type: "radio",
+                                class: "form-radio text-blue-500",
+                                name: "editor_mode",
+                                value: "false",
+                                checked: true,
+                                oninput: move |_ev| { *editor_mode.write() = false },
+                            }
+                            span { class: "ml-2 text-gray-700", "False" }
+                        }
+
+                        label { class: "inline-flex items-center",
+                            input {
+                                r#type: "radio",
+                                class: "form-radio text-blue-500",
+                                name: "editor_mode",
+                                value: "true",
+                                oninput: move |_ev| { *editor_mode.write() = true },
+                            }
+                            span { class: "ml-2 text-gray-700", "True" }
+                        }
+                    }
+                }
+
                 // Single player mode Radio Input
                 div { class: "space-y-2 border border-gray-300 rounded-lg p-2",
                     label { class: "block text-gray-700 font-semibold", "Single player mode" }
@@ -167,7 +203,7 @@ fn App() -> Element {
                             if ev.key() == Key::Enter {
                                 let value = san_content.read().to_owned();
                                 debug!("{value}");
-                                *action.write() = Some(Action::make_move(&value));
+                                chessboard.send(Action::make_move(&value));
                             }
                         },
                     }
@@ -187,9 +223,7 @@ fn App() -> Element {
                                 value: "default",
                                 checked: true,
                                 oninput: move |_ev| {
-                                    *action.write() = Some(
-                                        Action::set_position(ChessboardProps::default_position()),
-                                    );
+                                    chessboard.send(Action::set_position(ChessboardProps::default_position()));
                                 },
                             }
                             span { class: "ml-2 text-gray-700", "Starting position" }
@@ -202,7 +236,7 @@ fn App() -> Element {
                                 name: "position",
                                 value: "castling",
                                 oninput: move |_ev| {
-                                    *action.write() = Some(Action::set_position(&castling));
+                                    chessboard.send(Action::set_position(&castling));
                                 },
                             }
                             span { class: "ml-2 text-gray-700", "Test Castling" }
@@ -215,11 +249,24 @@ fn App() -> Element {
                                 name: "position",
                                 value: "promotion",
                                 oninput: move |_ev| {
-                                    *action.write() = Some(Action::set_position(&promotion));
+                                    chessboard.send(Action::set_position(&promotion));
                                 },
                             }
                             span { class: "ml-2 text-gray-700", "Test Promotion" }
                         }
+
+                        label { class: "inline-flex items-center",
+                            input {
+                                r#type: "radio",
+                                class: "form-radio text-blue-500",
+                                name: "position",
+                                value: "puzzle",
+                                oninput: move |_ev| {
+                                    chessboard.send(Action::load_puzzle(&puzzle, &["e1e8"]));
+                                },
+                            }
+                            span { class: "ml-2 text-gray-700", "Puzzle" }
+                        }
                     }
                 }
 
@@ -231,7 +278,7 @@ fn App() -> Element {
                         // «| (to first / “left-to-the-wall”)
                         button {
                             class: "p-1 rounded transition filter hover:bg-gray-400",
-                            onclick: move |_| action.set(Some(Action::set_start_position())),
+                            onclick: move |_| chessboard.send(Action::set_start_position()),
                             img {
                                 src: LEFT_WALL,
                                 alt: "First",
@@ -242,7 +289,7 @@ fn App() -> Element {
                         // ‹ (previous / “left”)
                         button {
                             class: "p-1 rounded transition filter hover:bg-gray-400",
-                            onclick: move |_| action.set(Some(Action::prev())),
+                            onclick: move |_| chessboard.send(Action::prev()),
                             img {
                                 src: LEFT,
                                 alt: "Previous",
@@ -253,7 +300,7 @@ fn App() -> Element {
                         // › (next / “right”)
                         button {
                             class: "p-1 rounded transition filter hover:bg-gray-400",
-                            onclick: move |_| action.set(Some(Action::next())),
+                            onclick: move |_| chessboard.send(Action::next()),
                             img {
                                 src: RIGHT,
                                 alt: "Next",
@@ -264,7 +311,7 @@ fn App() -> Element {
                         // |» (to last / “right-to-the-wall”)
                         button {
                             class: "p-1 rounded transition filter hover:bg-gray-400",
-                            onclick: move |_| action.set(Some(Action::set_end_position())),
+                            onclick: move |_| chessboard.send(Action::set_end_position()),
                             img {
                                 src: RIGHT_WALL,
                                 alt: "Last",
@@ -292,7 +339,7 @@ fn App() -> Element {
                     button {
                         class: "bg-blue-500 hover:bg-blue-700 text-white font-bold py-2 px-4 rounded",
                         onclick: move |_| {
-                            *action.write() = Some(Action::revert_move());
+                            chessboard.send(Action::revert_move());
                         },
                         "Revert last move"
                     }
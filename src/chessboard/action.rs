@@ -1,69 +1,192 @@
-use std::sync::atomic::AtomicU32;
-use std::sync::atomic::Ordering::Relaxed;
-
-/// Action counter to make every [`ActionInner`] unique, i.e., [`Action`].
-pub static NEXT_ACTION: AtomicU32 = AtomicU32::new(0);
-
-/// Keeps track of injected [`ActionInner`]'s have been processed.
-/// At initialization, this value must be different from the one in [`NEXT_ACTION`].
-pub static PROCESSED_ACTION: AtomicU32 = AtomicU32::new(1);
+#[cfg(feature = "serde")]
+use crate::history::BoardState;
+use crate::{Color, Shape};
 
+/// Action to be sent to a [`crate::Chessboard`] via its [`crate::ChessboardController`].
 #[derive(Debug, Clone, PartialEq)]
 pub struct Action {
-    /// Value allowing to discriminate instances of this variant.
-    pub(crate) discriminator: u32,
     pub(crate) action: ActionInner,
 }
 
 impl Action {
     /// Make a SAN-encoded move.
+    /// An illegal move is reported as [`crate::BoardAction::Rejected`],
+    /// as is a move made while a move of the player waits for a confirmation.
     pub fn make_move(m: &str) -> Self {
         Self {
-            discriminator: NEXT_ACTION.fetch_add(1, Relaxed),
             action: ActionInner::MakeSanMove(m.to_string()),
         }
     }
 
+    /// Commit the move of the player waiting for a confirmation, see [`crate::BoardAction::MoveProposed`].
+    /// Without a pending move, the action is reported as [`crate::BoardAction::Rejected`].
+    pub fn confirm_move() -> Action {
+        Self {
+            action: ActionInner::ConfirmMove,
+        }
+    }
+
+    /// Roll back the move of the player waiting for a confirmation.
+    /// Without a pending move, the action is reported as [`crate::BoardAction::Rejected`].
+    /// Until the move is confirmed or rolled back, navigation actions are rejected as well.
+    pub fn reject_move() -> Action {
+        Self {
+            action: ActionInner::RejectMove,
+        }
+    }
+
+    /// The `side` resigns, which ends the game.
+    pub fn resign(side: Color) -> Action {
+        Self {
+            action: ActionInner::Resign(side),
+        }
+    }
+
+    /// The `side` offers a draw, which is reported as [`crate::BoardAction::DrawOffered`].
+    pub fn offer_draw(side: Color) -> Action {
+        Self {
+            action: ActionInner::OfferDraw(side),
+        }
+    }
+
+    /// Accept the draw offer, which ends the game.
+    /// Without an offer, the action is reported as [`crate::BoardAction::Rejected`].
+    pub fn accept_draw() -> Action {
+        Self {
+            action: ActionInner::AcceptDraw,
+        }
+    }
+
     pub fn revert_move() -> Action {
         Self {
-            discriminator: NEXT_ACTION.fetch_add(1, Relaxed),
             action: ActionInner::RevertMove,
         }
     }
 
+    /// Set the position described in FEN notation, discarding the game history.
+    /// An invalid FEN is reported as [`crate::BoardAction::Rejected`].
     pub fn set_position(fen: &str) -> Action {
         Self {
-            discriminator: NEXT_ACTION.fetch_add(1, Relaxed),
             action: ActionInner::SetPosition {
                 fen: fen.to_string(),
             },
         }
     }
 
+    /// Load a game in PGN, which can be navigated with [`Action::prev`] and [`Action::next`].
+    /// A malformed game is reported as [`crate::BoardAction::Rejected`].
+    pub fn load_pgn(pgn: &str) -> Action {
+        Self {
+            action: ActionInner::LoadPgn {
+                pgn: pgn.to_string(),
+            },
+        }
+    }
+
+    /// Load a puzzle: the position in FEN notation and the expected solution line.
+    /// Moves of the solution are encoded either in UCI or in SAN.
+    /// They start with a move of the side to move, i.e., the player,
+    /// and alternate with the replies of the opponent, which are played automatically.
+    /// Only correct moves of the player are accepted, any checkmating move is correct.
+    /// The outcome is reported as [`crate::BoardAction::PuzzleSolved`] or [`crate::BoardAction::PuzzleFailed`].
+    /// Once the puzzle is over, no moves can be made until another position is set.
+    pub fn load_puzzle(fen: &str, solution: &[&str]) -> Action {
+        Self {
+            action: ActionInner::LoadPuzzle {
+                fen: fen.to_string(),
+                solution: solution.iter().map(|m| m.to_string()).collect(),
+            },
+        }
+    }
+
+    /// Switch to the next variation branching off with the move leading to the current position.
+    pub fn next_variation() -> Action {
+        Self {
+            action: ActionInner::NextVariation,
+        }
+    }
+
+    /// Switch to the previous variation branching off with the move leading to the current position.
+    pub fn prev_variation() -> Action {
+        Self {
+            action: ActionInner::PrevVariation,
+        }
+    }
+
+    /// Make the variation containing the current position the main line.
+    pub fn promote_variation() -> Action {
+        Self {
+            action: ActionInner::PromoteVariation,
+        }
+    }
+
+    /// Delete the variation containing the current position.
+    pub fn delete_variation() -> Action {
+        Self {
+            action: ActionInner::DeleteVariation,
+        }
+    }
+
+    /// Request the game history in PGN.
+    /// It is delivered as [`crate::BoardAction::Pgn`] to the channel of board actions.
+    pub fn export_pgn() -> Action {
+        Self {
+            action: ActionInner::ExportPgn,
+        }
+    }
+
+    /// Request the complete game, including all variations and the current position.
+    /// It is delivered as [`crate::BoardAction::State`] to the channel of board actions.
+    #[cfg(feature = "serde")]
+    pub fn export_state() -> Action {
+        Self {
+            action: ActionInner::ExportState,
+        }
+    }
+
+    /// Restore a game exported with [`Action::export_state`], replacing the current one.
+    /// An inconsistent state, e.g., with an illegal move, is reported as [`crate::BoardAction::Rejected`].
+    #[cfg(feature = "serde")]
+    pub fn restore(state: BoardState) -> Action {
+        Self {
+            action: ActionInner::Restore(Box::new(state)),
+        }
+    }
+
+    /// Draw the shapes over the board, replacing the ones drawn before.
+    pub fn set_shapes(shapes: &[Shape]) -> Action {
+        Self {
+            action: ActionInner::SetShapes(shapes.to_vec()),
+        }
+    }
+
+    /// Remove all shapes drawn over the board.
+    pub fn clear_shapes() -> Action {
+        Self {
+            action: ActionInner::SetShapes(vec![]),
+        }
+    }
+
     pub fn set_start_position() -> Action {
         Self {
-            discriminator: NEXT_ACTION.fetch_add(1, Relaxed),
             action: ActionInner::SetStartPosition,
         }
     }
 
     pub fn set_end_position() -> Action {
         Self {
-            discriminator: NEXT_ACTION.fetch_add(1, Relaxed),
             action: ActionInner::SetEndPosition,
         }
     }
 
     pub fn prev() -> Action {
         Self {
-            discriminator: NEXT_ACTION.fetch_add(1, Relaxed),
             action: ActionInner::StepBack,
         }
     }
 
     pub fn next() -> Action {
         Self {
-            discriminator: NEXT_ACTION.fetch_add(1, Relaxed),
             action: ActionInner::StepForward,
         }
     }
@@ -73,13 +196,55 @@ impl Action {
 #[derive(Debug, Clone, PartialEq)]
 pub(crate) enum ActionInner {
     MakeSanMove(String),
+    ConfirmMove,
+    RejectMove,
+    Resign(Color),
+    OfferDraw(Color),
+    AcceptDraw,
     RevertMove,
     SetPosition {
         /// String FEN representation of the position.
         fen: String,
     },
+    LoadPgn {
+        /// Game in PGN.
+        pgn: String,
+    },
+    LoadPuzzle {
+        /// String FEN representation of the position.
+        fen: String,
+        /// UCI- or SAN-encoded moves of the solution.
+        solution: Vec<String>,
+    },
+    ExportPgn,
+    #[cfg(feature = "serde")]
+    ExportState,
+    #[cfg(feature = "serde")]
+    Restore(Box<BoardState>),
+    SetShapes(Vec<Shape>),
     StepBack,
     StepForward,
     SetStartPosition,
     SetEndPosition,
+    NextVariation,
+    PrevVariation,
+    PromoteVariation,
+    DeleteVariation,
+}
+
+impl ActionInner {
+    /// Does the action move the board to another position of the game?
+    pub(crate) fn is_navigation(&self) -> bool {
+        matches!(
+            self,
+            Self::RevertMove
+                | Self::StepBack
+                | Self::StepForward
+                | Self::SetStartPosition
+                | Self::SetEndPosition
+                | Self::NextVariation
+                | Self::PrevVariation
+                | Self::DeleteVariation
+        )
+    }
 }
@@ -1,56 +1,362 @@
-use crate::chessboard::action::{Action, ActionInner, PROCESSED_ACTION};
+use crate::chessboard::action::{Action, ActionInner};
 use crate::chessboard::properties::ChessboardProps;
+use crate::chessboard::SanMove;
+use crate::clock::Clock;
+use crate::controlled::ControlledPosition;
+use crate::drag::{coord_at, Drag, DragSource};
+use crate::editor::{edit, EditedPosition, EditorControls, SparePieces};
+use crate::engine::use_engine_analysis;
 use crate::files::Files;
-use crate::history::HistoricalBoard;
-use crate::move_builder::MoveBuilder;
+use crate::history::{BoardAction, HistoricalBoard, HistoricalBoardError};
+use crate::keyboard::{move_focus, KeyboardFocus};
+use crate::move_builder::{MoveBuilder, PlayerMoves};
+#[cfg(feature = "persist")]
+use crate::persist::{load, use_persistence};
+use crate::premove::Premoves;
 use crate::promotion::Promotion;
+use crate::puzzle::Puzzle;
 use crate::ranks::Ranks;
-use crate::square::Square;
+use crate::shapes::{report_shapes, toggle, Drawing, Shape, Shapes};
+use crate::square::{MoveHint, Square};
+#[cfg(feature = "sync")]
+use crate::sync::use_board_sync;
+use crate::{apply_move, finalize};
+use dioxus::html::geometry::PixelsRect;
 use dioxus::prelude::*;
+use futures_timer::Delay;
 use owlchess::board::PrettyStyle;
-use owlchess::{Color, Coord, File, Rank};
-use std::sync::atomic::Ordering::Relaxed;
+use owlchess::{Board, Color, Coord, File, Move, Rank};
+use std::cell::Cell;
+use std::rc::Rc;
+use std::time::Duration;
 use tracing::{debug, info, warn};
 
 const CHESSBOARD_STYLES: Asset = asset!("/public/css/chessboard.css");
 
+/// Interval of checking whether a pending move has timed out.
+const CONFIRMATION_TICK: Duration = Duration::from_millis(100);
+
 /// Component rendering [Chessboard].
+///
+/// With the keyboard, Left and Right step through the history, Home and End jump to its ends.
+/// Up and Down, as well as Ctrl (or Meta) with Left and Right, move the focus between the squares,
+/// Enter and Space select the focused square.
 #[component]
 pub fn Chessboard(props: ChessboardProps) -> Element {
     let props = props.complete();
     debug!("Rendering with properties: {props:#?}");
 
+    // Shapes saved with a restored game, which are drawn again.
+    let restored_shapes = Cell::new(None::<Vec<Shape>>);
+
     // Initialize the move history.
+    // An invalid starting position is reported and replaced with the default one.
+    // A controlled board starts in the position of the host, otherwise a saved game is restored.
     use_context_provider(|| {
-        Signal::new(
-            HistoricalBoard::initialize(&props.starting_position, props.san_tx)
-                .expect("Valid FEN position description is expected"),
-        )
+        let restored = props.position.and_then(|position| {
+            position
+                .peek()
+                .resolve(&props.starting_position)
+                .and_then(|(start, moves)| HistoricalBoard::from_line(start, &moves, props.san_tx))
+                .inspect_err(|err| warn!("Controlled position cannot be set: {err}"))
+                .ok()
+        });
+
+        #[cfg(feature = "persist")]
+        let restored = restored.or_else(|| {
+            props
+                .persist_key
+                .as_ref()
+                .and_then(|key| load(key, props.san_tx))
+                .map(|(historical_board, shapes)| {
+                    restored_shapes.set(Some(shapes));
+                    historical_board
+                })
+        });
+
+        let initialized = match restored {
+            Some(historical_board) => Ok(historical_board),
+            None => HistoricalBoard::initialize(&props.starting_position, props.san_tx),
+        };
+
+        let mut historical_board = initialized.unwrap_or_else(|err| {
+            warn!(
+                "Starting position {} cannot be set: {err}",
+                props.starting_position
+            );
+
+            let historical_board =
+                HistoricalBoard::initialize(ChessboardProps::default_position(), props.san_tx)
+                    .expect("Default position is valid");
+            reject(
+                &historical_board,
+                Action::set_position(&props.starting_position),
+                err,
+            );
+
+            historical_board
+        });
+
+        historical_board.set_clocks(
+            props.time_control,
+            props.clock_source.clone().unwrap_or_default(),
+        );
+
+        Signal::new(historical_board)
+    });
+
+    // Moves of the player are proposed to the host controlling the board,
+    // or wait for its confirmation if it is required.
+    use_context_provider(|| match (props.position, props.confirmation_timeout) {
+        (Some(_), _) => PlayerMoves::Proposed,
+        (None, Some(timeout)) => PlayerMoves::Confirmed { timeout },
+        (None, None) => PlayerMoves::Applied,
     });
 
     // Initialize the move builder.
     use_context_provider(|| Signal::new(MoveBuilder::new()));
 
+    // Initialize the dragging state.
+    use_context_provider(|| Signal::new(None::<Drag>));
+
+    // Initialize the board editor, which is only present in the editor mode.
+    use_context_provider(|| Signal::new(None::<EditedPosition>));
+
+    // Initialize the puzzle, which is only present while solving it.
+    use_context_provider(|| Signal::new(None::<Puzzle>));
+
+    // Initialize the premoves queued while the opponent is thinking.
+    use_context_provider(|| Signal::new(Premoves::new()));
+
+    // Initialize the shapes drawn over the board and the one being drawn.
+    use_context_provider(|| Signal::new(restored_shapes.take().unwrap_or_default()));
+    use_context_provider(|| Signal::new(None::<Drawing>));
+
+    // Every change of the game and its shapes is saved.
+    #[cfg(feature = "persist")]
+    use_persistence(props.persist_key.clone());
+
+    // Initialize the square focused with the keyboard.
+    use_context_provider(|| Signal::new(KeyboardFocus::default()));
+
     let mut historical_board = use_context::<Signal<HistoricalBoard>>();
     let mut move_builder = use_context::<Signal<MoveBuilder>>();
+    let mut drag = use_context::<Signal<Option<Drag>>>();
+    let mut editor = use_context::<Signal<Option<EditedPosition>>>();
+    let mut puzzle = use_context::<Signal<Option<Puzzle>>>();
+    let mut premoves = use_context::<Signal<Premoves>>();
+    let mut shapes = use_context::<Signal<Vec<Shape>>>();
+    let mut drawing = use_context::<Signal<Option<Drawing>>>();
+    let mut focus = use_context::<Signal<KeyboardFocus>>();
+    let player_moves = use_context::<PlayerMoves>();
+
+    // Entering the editor mode starts editing the current position,
+    // leaving it starts the game from the edited position if it is legal,
+    // otherwise the edits are discarded and reported as a rejected position.
+    let editor_mode = props.editor_mode;
+    use_effect(use_reactive!(|editor_mode| {
+        if editor_mode {
+            move_builder.write().cancel();
+            editor.set(Some(EditedPosition::new(&historical_board.peek())));
+            return;
+        }
+
+        let Some(position) = editor.take() else {
+            return;
+        };
+
+        let fen = position.as_fen();
+        let initialized = position.validate().and_then(|board| {
+            HistoricalBoard::initialize(&board.as_fen(), historical_board.peek().move_tx)
+        });
+
+        match initialized {
+            Ok(board) => {
+                move_builder.set(MoveBuilder::new());
+                replace_board(&mut historical_board, &mut premoves, board);
+                puzzle.set(None);
+            }
+            Err(err) => {
+                warn!("Edited position {fen} is not legal, the game continues: {err}");
+                reject(&historical_board.peek(), Action::set_position(&fen), err);
+            }
+        }
+    }));
+
+    let is_editing = editor.read().is_some();
+
+    // Board element and its bounding rectangle to locate dropped pieces.
+    let mut board_element = use_signal(|| None::<Rc<MountedData>>);
+    let mut board_rect = use_signal(|| None::<PixelsRect>);
 
     // Compute if the board is interactive for the **player**.
-    let is_interactive = {
+    let (is_interactive, is_premoving) = {
         let side_to_move = historical_board.read().side_to_move();
+        let is_game_over = historical_board.read().game_over().is_some();
 
         // Board is interactive if
         // - it is configured to be interactive, and
         // - either it is in the analysis mode,
         // - or
-        //   - the next move is expected from the configured player.
-        // (general interactivity)   (analysis mode)          (a right color piece move)
-        props.is_interactive && (!props.single_player_mode || side_to_move == props.color)
+        //   - the next move is expected from the configured player, and
+        // - the game has not ended, unless play after the game end is allowed, and
+        // - no position is being edited, and
+        // - the player is expected to move if a puzzle is being solved, and
+        // - no move of the player waits for the confirmation of the host.
+        let is_playable = props.is_interactive
+            && !is_editing
+            && (!is_game_over || props.interactive_after_game_over);
+
+        let is_interactive = is_playable
+            && puzzle.read().as_ref().is_none_or(Puzzle::is_player_turn)
+            && move_builder.read().pending().is_none()
+            && (!props.single_player_mode || side_to_move == props.color);
+
+        // While the opponent is thinking in the single-player mode, the player can queue premoves.
+        let is_premoving = is_playable
+            && puzzle.read().is_none()
+            && props.single_player_mode
+            && side_to_move != props.color;
+
+        (is_interactive, is_premoving)
     };
 
-    if let Some(action) = props.action {
-        // Board always accepts actions.
-        maybe_update_board(action, &mut historical_board, &mut move_builder);
-    }
+    // Once it is the player's turn at the end of the line,
+    // the first premove is played if it is legal in the new position.
+    let player_color = props.color;
+    let single_player_mode = props.single_player_mode;
+    use_effect(use_reactive!(|player_color, single_player_mode| {
+        let board = historical_board.read();
+
+        if !single_player_mode || board.game_over().is_some() {
+            if !premoves.peek().is_empty() {
+                premoves.write().clear();
+            }
+            return;
+        }
+
+        if premoves.peek().is_empty()
+            || !board.is_at_end()
+            || board.side_to_move() != player_color
+            || !matches!(*move_builder.peek(), MoveBuilder::None)
+        {
+            return;
+        }
+
+        let premove = premoves.write().take_legal(&board);
+        if let Some(m) = premove {
+            debug!("Playing premove {m}");
+            move_builder.write().premove(m);
+        }
+    }));
+
+    // A controlled board follows the position of the host.
+    let position = props.position;
+    let starting_position = props.starting_position.clone();
+    use_effect(move || {
+        if let Some(position) = position {
+            follow(
+                &position.read(),
+                &starting_position,
+                &mut historical_board,
+                &mut move_builder,
+                &mut puzzle,
+                &mut premoves,
+            );
+        }
+    });
+
+    // A move not confirmed by the host in time is rolled back.
+    let confirmation_timeout = props.confirmation_timeout;
+    use_future(move || async move {
+        if confirmation_timeout.is_none() {
+            return;
+        }
+
+        loop {
+            Delay::new(CONFIRMATION_TICK).await;
+
+            let Some((m, deadline)) = move_builder.peek().pending() else {
+                continue;
+            };
+
+            let board = historical_board.peek();
+            if board.clock_source().now() < deadline {
+                continue;
+            }
+
+            warn!("Move {m} has not been confirmed in time, rolling it back");
+
+            // The game might have been replaced in the meantime.
+            if let (Some(tx), Ok(())) = (board.move_tx.as_ref(), m.validate(&board)) {
+                tx.send(BoardAction::MoveTimedOut(SanMove::from_move(&board, m)));
+            }
+            move_builder.write().reject();
+        }
+    });
+
+    // Animations are disabled if the user prefers reduced motion.
+    let mut prefers_reduced_motion = use_signal(|| false);
+    use_future(move || async move {
+        let query =
+            document::eval("return window.matchMedia('(prefers-reduced-motion: reduce)').matches;");
+
+        if let Ok(true) = query.join::<bool>().await {
+            debug!("Animations are disabled for the reduced motion preference");
+            prefers_reduced_motion.set(true);
+        }
+    });
+
+    let animation_duration = if prefers_reduced_motion() {
+        Duration::ZERO
+    } else {
+        props.animation_duration
+    };
+
+    // Without animations, no transition ends to finalize a move, so it is finalized right away.
+    use_effect(use_reactive!(|animation_duration| {
+        let is_animated = !move_builder.read().animations().is_empty();
+        if animation_duration.is_zero() && is_animated && move_builder.read().pending().is_none() {
+            finalize(
+                &mut move_builder,
+                &mut historical_board,
+                &mut puzzle,
+                player_moves,
+            );
+        }
+    }));
+
+    // Board always accepts actions sent via its controller.
+    // Actions sent while a position is edited wait until the editor mode is left,
+    // so that the edited position does not override them.
+    let controller = props.controller;
+    use_effect(move || {
+        let Some(controller) = controller else {
+            return;
+        };
+
+        if editor.read().is_some() {
+            return;
+        }
+
+        for action in controller.take_actions() {
+            update_board(
+                action,
+                &mut historical_board,
+                &mut move_builder,
+                &mut puzzle,
+                &mut premoves,
+                &mut shapes,
+            );
+        }
+    });
+
+    // Attached engine analyses the current position.
+    use_engine_analysis(props.engine.clone());
+
+    // Game is shared with the peer over the attached connection.
+    #[cfg(feature = "sync")]
+    use_board_sync(props.sync.clone());
 
     let (files, ranks) = match props.color {
         Color::White => (
@@ -63,8 +369,59 @@ pub fn Chessboard(props: ChessboardProps) -> Element {
         ),
     };
 
+    // Hints for the legal moves of the selected piece.
+    let move_hints = if props.show_move_hints && !is_editing {
+        move_builder.read().move_hints(&historical_board.read())
+    } else {
+        vec![]
+    };
+
+    let move_hint = |coord: Coord| {
+        move_hints
+            .iter()
+            .find(|(dst, _)| *dst == coord)
+            .map(|(_, is_capture)| {
+                if *is_capture {
+                    MoveHint::Capture
+                } else {
+                    MoveHint::Move
+                }
+            })
+    };
+
+    // Squares of the move leading to the current position and the king in check, if any.
+    let (last_move, king_in_check) = if is_editing {
+        (None, None)
+    } else {
+        let board = historical_board.read();
+        let last_move = board.get_previous_move().map(|m| (m.src(), m.dst()));
+        let king_in_check = board.is_check().then(|| board.king_pos(board.side()));
+        (last_move, king_in_check)
+    };
+
+    // Before any square is focused, the bottom left one is reachable with the Tab key.
+    let tab_stop = focus
+        .read()
+        .0
+        .unwrap_or_else(|| Coord::from_parts(files[0], ranks[ranks.len() - 1]));
+
+    // Move leading to the current position is announced to screen readers.
+    let announcement = if is_editing {
+        None
+    } else {
+        historical_board.read().previous_san()
+    };
+
     let mut chessboard_classes = vec!["chessboard"];
 
+    if props.is_draggable || is_editing {
+        chessboard_classes.push("draggable");
+    }
+
+    if props.theme.texture.is_some() {
+        chessboard_classes.push("textured");
+    }
+
     if move_builder.read().check_promotion().is_some() {
         // Promotion is required.
         chessboard_classes.push("opacity-25");
@@ -73,55 +430,324 @@ pub fn Chessboard(props: ChessboardProps) -> Element {
     rsx! {
         document::Link { rel: "stylesheet", href: CHESSBOARD_STYLES }
 
-        div { position: "relative",
-            div { class: chessboard_classes.join(" "),
-                for r in ranks.iter().cloned() {
-                    div { class: "row",
-                        for f in files.iter().cloned() {
-                            Square {
-                                is_interactive,
-                                coord: Coord::from_parts(f, r),
-                                color: props.color,
-                                pieces_set: props.pieces_set,
+        // Pointer events are handled around the board, so that spare pieces of the editor can be dragged onto it.
+        // The theme applies to this board only.
+        div {
+            style: props.theme.style(),
+            onpointerdown: move |_ev| {
+                if drag.peek().is_none() && drawing.peek().is_none()
+                    && premoves.peek().selected().is_none()
+                {
+                    return;
+                }
+
+                // The board might have been resized or scrolled since the last drag.
+                let Some(element) = board_element.peek().clone() else {
+                    return;
+                };
+
+                spawn(async move {
+                    board_rect.set(element.get_client_rect().await.ok());
+                });
+            },
+            onpointermove: {
+                let files = files.clone();
+                let ranks = ranks.clone();
+
+                move |ev: PointerEvent| {
+                    if drag.peek().is_some() {
+                        if let Some(drag) = drag.write().as_mut() {
+                            drag.move_to(ev.client_coordinates());
+                        }
+                    }
+
+                    // The shape follows the pointer while it is over the board.
+                    let Some(rect) = *board_rect.peek() else {
+                        return;
+                    };
+                    let Some(dst) = coord_at(ev.client_coordinates(), rect, &files, &ranks) else {
+                        return;
+                    };
+                    if drawing.peek().is_some_and(|drawing| drawing.dst() != dst) {
+                        if let Some(drawing) = drawing.write().as_mut() {
+                            drawing.move_to(dst);
+                        }
+                    }
+                }
+            },
+            onpointerup: {
+                let files = files.clone();
+                let ranks = ranks.clone();
+
+                move |ev: PointerEvent| {
+                    if let Some(drawn) = drawing.take() {
+                        toggle(&mut shapes.write(), drawn.shape());
+                        report_shapes(&historical_board.peek(), &shapes.peek());
+                        return;
+                    }
+
+                    // A premove can be completed by dragging the piece to its destination.
+                    let premove_src = premoves.peek().selected();
+                    if let Some(src) = premove_src {
+                        let dst = board_rect
+                            .peek()
+                            .and_then(|rect| coord_at(ev.client_coordinates(), rect, &files, &ranks));
+
+                        if let Some(dst) = dst.filter(|&dst| dst != src) {
+                            premoves
+                                .write()
+                                .put_square_coord(dst, &historical_board.peek(), props.color);
+                        }
+                        return;
+                    }
+
+                    let Some(drag) = drag.take() else {
+                        return;
+                    };
+
+                    // Until the board geometry is known, releasing is treated as a click.
+                    let dst = match (*board_rect.peek(), drag.src) {
+                        (Some(rect), _) => coord_at(ev.client_coordinates(), rect, &files, &ranks),
+                        (None, DragSource::Square(src)) => Some(src),
+                        (None, DragSource::Spare(_)) => None,
+                    };
+
+                    if is_editing {
+                        drop_edited_piece(drag, dst, &mut editor, &historical_board);
+                    } else {
+                        drop_piece(drag, dst, &historical_board, &mut move_builder);
+                    }
+                }
+            },
+            onpointerleave: move |_ev| {
+                if drawing.peek().is_some() {
+                    drawing.set(None);
+                }
+
+                if let Some(drag) = drag.take() {
+                    if !is_editing {
+                        drop_piece(drag, None, &historical_board, &mut move_builder);
+                    }
+                }
+            },
+            if is_editing {
+                SparePieces { color: props.color.inv(), pieces_set: props.pieces_set.clone() }
+            }
+            div { position: "relative",
+                div {
+                    class: chessboard_classes.join(" "),
+                    role: "grid",
+                    aria_label: "Chessboard",
+                    onmounted: move |ev| async move {
+                        let element = ev.data();
+                        board_rect.set(element.get_client_rect().await.ok());
+                        board_element.set(Some(element));
+                    },
+                    // Left, Right, Home and End navigate through the history,
+                    // Up and Down, as well as Ctrl (or Meta) with Left and Right, move the focus between the squares.
+                    onkeydown: {
+                        let files = files.clone();
+                        let ranks = ranks.clone();
+
+                        move |ev: KeyboardEvent| {
+                            let is_ctrl = ev.modifiers().intersects(Modifiers::CONTROL | Modifiers::META);
+
+                            let action = match ev.key() {
+                                Key::Home => Some(Action::set_start_position()),
+                                Key::End => Some(Action::set_end_position()),
+                                Key::ArrowLeft if !is_ctrl => Some(Action::prev()),
+                                Key::ArrowRight if !is_ctrl => Some(Action::next()),
+                                key => {
+                                    let focused = focus.peek().0.unwrap_or(tab_stop);
+                                    if let Some(coord) = move_focus(focused, &key, &files, &ranks) {
+                                        ev.prevent_default();
+                                        focus.set(KeyboardFocus(Some(coord)));
+                                    }
+                                    None
+                                }
+                            };
+
+                            if let Some(action) = action {
+                                ev.prevent_default();
+                                if !is_editing {
+                                    update_board(
+                                        action,
+                                        &mut historical_board,
+                                        &mut move_builder,
+                                        &mut puzzle,
+                                        &mut premoves,
+                                        &mut shapes,
+                                    );
+                                }
+                            }
+                        }
+                    },
+                    for r in ranks.iter().cloned() {
+                        div { class: "row", role: "row",
+                            for f in files.iter().cloned() {
+                                Square {
+                                    is_interactive,
+                                    is_premoving,
+                                    is_focused: focus.read().0 == Some(Coord::from_parts(f, r)),
+                                    is_tab_stop: tab_stop == Coord::from_parts(f, r),
+                                    is_draggable: props.is_draggable,
+                                    animation_duration,
+                                    is_editing,
+                                    move_hint: move_hint(Coord::from_parts(f, r)),
+                                    is_last_move: last_move
+                                        .is_some_and(|(src, dst)| {
+                                            src == Coord::from_parts(f, r) || dst == Coord::from_parts(f, r)
+                                        }),
+                                    is_check: king_in_check == Some(Coord::from_parts(f, r)),
+                                    coord: Coord::from_parts(f, r),
+                                    color: props.color,
+                                    pieces_set: props.pieces_set.clone(),
+                                }
                             }
                         }
                     }
                 }
+                Ranks { color: props.color }
+                Files { color: props.color }
+                Shapes { color: props.color }
+                Promotion { color: props.color, pieces_set: props.pieces_set.clone() }
+            }
+            div { class: "sr-only", aria_live: "polite", aria_atomic: "true", {announcement} }
+            if is_editing {
+                SparePieces { color: props.color, pieces_set: props.pieces_set.clone() }
+                EditorControls {}
+            }
+            if props.time_control.is_some() {
+                Clock { color: props.color }
             }
-            Ranks { color: props.color }
-            Files { color: props.color }
-            Promotion { color: props.color, pieces_set: props.pieces_set }
         }
     }
 }
 
-/// Examine [Action] and apply respective changes if the action has not yet been processed.
-/// If the action was processed, does nothing.
-fn maybe_update_board(
+/// Completes dragging a piece by either dropping it onto the `dst` square or cancelling the move.
+/// An illegal drop returns the piece to its source square.
+fn drop_piece(
+    drag: Drag,
+    dst: Option<Coord>,
+    historical_board: &Signal<HistoricalBoard>,
+    move_builder: &mut Signal<MoveBuilder>,
+) {
+    let DragSource::Square(src) = drag.src else {
+        return;
+    };
+
+    let mut move_builder = move_builder.write();
+
+    // The move might have been overridden by an injected action while dragging.
+    if move_builder.selected() != Some(src) {
+        return;
+    }
+
+    match dst {
+        // The piece stays selected to allow click-click moves, unless it is released after a repeated press.
+        Some(dst) if dst == src => {
+            if drag.deselect_on_release {
+                move_builder.cancel();
+            }
+        }
+        Some(dst) => move_builder.put_square_coord(dst, &historical_board.read()),
+        None => move_builder.cancel(),
+    }
+}
+
+/// Completes dragging a piece in the editor mode.
+/// A spare piece is placed onto the `dst` square, a piece of the board is moved there.
+/// A piece dropped outside the board is removed.
+fn drop_edited_piece(
+    drag: Drag,
+    dst: Option<Coord>,
+    editor: &mut Signal<Option<EditedPosition>>,
+    historical_board: &Signal<HistoricalBoard>,
+) {
+    match (drag.src, dst) {
+        (DragSource::Square(src), Some(dst)) if src == dst => {}
+        (DragSource::Square(src), Some(dst)) => {
+            edit(editor, historical_board, |position| {
+                position.move_piece(src, dst)
+            });
+        }
+        (DragSource::Square(src), None) => {
+            edit(editor, historical_board, |position| position.remove(src));
+        }
+        (DragSource::Spare(cell), Some(dst)) => {
+            edit(editor, historical_board, |position| position.put(dst, cell));
+        }
+        (DragSource::Spare(_), None) => {}
+    }
+}
+
+/// Examine [Action] and apply respective changes.
+fn update_board(
     action: Action,
     historical_board: &mut Signal<HistoricalBoard>,
     move_builder: &mut Signal<MoveBuilder>,
+    puzzle: &mut Signal<Option<Puzzle>>,
+    premoves: &mut Signal<Premoves>,
+    shapes: &mut Signal<Vec<Shape>>,
 ) {
-    let processed_action = PROCESSED_ACTION.load(Relaxed);
-    if processed_action == action.discriminator {
-        debug!("Action {action:?} has already been processed");
+    debug!("Received action: {action:?}");
+
+    // Puzzle moves are judged in the puzzle position, so the board stays there until the puzzle is over.
+    if action.action.is_navigation() && puzzle.read().as_ref().is_some_and(Puzzle::is_playing) {
+        warn!("Board cannot leave the puzzle position while it is being solved");
+        reject(
+            &historical_board.read(),
+            action,
+            HistoricalBoardError::PuzzleInProgress,
+        );
         return;
     }
-    PROCESSED_ACTION.store(action.discriminator, Relaxed);
 
-    debug!("Received action: {action:?}");
+    // A move waiting for the confirmation of the host must be confirmed or rolled back first.
+    let is_pending = move_builder.peek().pending().is_some();
+    if is_pending
+        && (action.action.is_navigation() || matches!(action.action, ActionInner::MakeSanMove(_)))
+    {
+        warn!("Action {action:?} cannot be applied while a move waits for a confirmation");
+        reject(
+            &historical_board.read(),
+            action,
+            HistoricalBoardError::MovePending,
+        );
+        return;
+    }
+
+    // Premoves are queued for the position at the end of the line, which the board leaves now.
+    if action.action.is_navigation() && !premoves.peek().is_empty() {
+        premoves.write().clear();
+    }
 
-    match action.action {
+    match &action.action {
         ActionInner::MakeSanMove(san) => {
             let board = historical_board.read();
 
-            if move_builder.write().apply_san_move(&san, &board).is_ok() {
-                info!("Injected move: {san}");
-            } else {
-                warn!(
-                    "Injected move {san} is not legal in the current position\n{}",
-                    board.pretty(PrettyStyle::Utf8)
-                );
+            match move_builder.write().apply_san_move(san, &board) {
+                Ok(()) => info!("Injected move: {san}"),
+                Err(err) => {
+                    warn!(
+                        "Injected move {san} is not legal in the current position\n{}",
+                        board.pretty(PrettyStyle::Utf8)
+                    );
+                    reject(&board, action.clone(), err.into());
+                }
+            }
+        }
+
+        ActionInner::Resign(side) => historical_board.write().resign(*side),
+
+        ActionInner::OfferDraw(side) => historical_board.write().offer_draw(*side),
+
+        ActionInner::AcceptDraw => {
+            let accepted = historical_board.write().accept_draw();
+
+            if let Err(err) = accepted {
+                warn!("Draw cannot be agreed: {err}");
+                reject(&historical_board.read(), action.clone(), err);
             }
         }
 
@@ -132,12 +758,133 @@ fn maybe_update_board(
         }
 
         ActionInner::SetPosition { fen } => {
-            let move_tx = historical_board.write().move_tx.take();
+            let move_tx = historical_board.read().move_tx;
 
-            historical_board.set(
-                HistoricalBoard::initialize(&fen, move_tx)
-                    .expect("Valid FEN position description is expected"),
-            );
+            match HistoricalBoard::initialize(fen, move_tx) {
+                Ok(board) => {
+                    move_builder.set(MoveBuilder::new());
+                    replace_board(historical_board, premoves, board);
+                    puzzle.set(None);
+                }
+                Err(err) => {
+                    warn!("Injected position {fen} cannot be set: {err}");
+                    reject(&historical_board.read(), action.clone(), err);
+                }
+            }
+        }
+
+        ActionInner::LoadPgn { pgn } => {
+            let move_tx = historical_board.read().move_tx;
+
+            match HistoricalBoard::from_pgn(pgn, move_tx) {
+                Ok(board) => {
+                    debug!(
+                        "Loaded PGN game {:?} with result {}",
+                        board.tags(),
+                        board.result()
+                    );
+                    move_builder.set(MoveBuilder::new());
+                    replace_board(historical_board, premoves, board);
+                    puzzle.set(None);
+                }
+                Err(err) => {
+                    warn!("Injected PGN cannot be loaded: {err}");
+                    reject(&historical_board.read(), action.clone(), err);
+                }
+            }
+        }
+
+        ActionInner::LoadPuzzle { fen, solution } => {
+            let move_tx = historical_board.read().move_tx;
+
+            let loaded = HistoricalBoard::initialize(fen, move_tx).and_then(|board| {
+                let loaded_puzzle = Puzzle::new(&board, solution)?;
+                Ok((board, loaded_puzzle))
+            });
+
+            match loaded {
+                Ok((board, loaded_puzzle)) => {
+                    move_builder.write().cancel();
+                    replace_board(historical_board, premoves, board);
+                    puzzle.set(Some(loaded_puzzle));
+                }
+                Err(err) => {
+                    warn!("Injected puzzle cannot be loaded: {err}");
+                    reject(&historical_board.read(), action.clone(), err);
+                }
+            }
+        }
+
+        ActionInner::ExportPgn => {
+            let board = historical_board.read();
+
+            if let Some(tx) = board.move_tx.as_ref() {
+                tx.send(BoardAction::Pgn(board.to_pgn()));
+            }
+        }
+
+        #[cfg(feature = "serde")]
+        ActionInner::ExportState => {
+            let board = historical_board.read();
+
+            if let Some(tx) = board.move_tx.as_ref() {
+                tx.send(BoardAction::State(Box::new(board.to_state(&shapes.peek()))));
+            }
+        }
+
+        #[cfg(feature = "serde")]
+        ActionInner::Restore(state) => {
+            let move_tx = historical_board.read().move_tx;
+
+            match HistoricalBoard::from_state(state, move_tx) {
+                Ok(board) => {
+                    move_builder.set(MoveBuilder::new());
+                    replace_board(historical_board, premoves, board);
+                    puzzle.set(None);
+                    shapes.set(state.shapes().to_vec());
+                }
+                Err(err) => {
+                    warn!("Game cannot be restored: {err}");
+                    reject(&historical_board.read(), action.clone(), err);
+                }
+            }
+        }
+
+        ActionInner::SetShapes(new_shapes) => shapes.set(new_shapes.clone()),
+
+        ActionInner::ConfirmMove => {
+            let confirmed = move_builder.write().confirm();
+
+            match confirmed {
+                Some(m) => {
+                    info!("Move {m} is confirmed");
+                    apply_move(m, move_builder, historical_board, puzzle);
+                }
+                None => {
+                    warn!("No move waits for a confirmation");
+                    reject(
+                        &historical_board.read(),
+                        action.clone(),
+                        HistoricalBoardError::NoPendingMove,
+                    );
+                }
+            }
+        }
+
+        ActionInner::RejectMove => {
+            let rejected = move_builder.write().reject();
+
+            match rejected {
+                Some(m) => info!("Move {m} is rejected"),
+                None => {
+                    warn!("No move waits for a confirmation");
+                    reject(
+                        &historical_board.read(),
+                        action.clone(),
+                        HistoricalBoardError::NoPendingMove,
+                    );
+                }
+            }
         }
 
         ActionInner::StepBack => {
@@ -153,5 +900,90 @@ fn maybe_update_board(
         }
         ActionInner::SetStartPosition => historical_board.write().set_start(),
         ActionInner::SetEndPosition => historical_board.write().set_end(),
+        ActionInner::NextVariation => historical_board.write().switch_variation(true),
+        ActionInner::PrevVariation => historical_board.write().switch_variation(false),
+        ActionInner::PromoteVariation => historical_board.write().promote_variation(),
+        ActionInner::DeleteVariation => historical_board.write().delete_variation(),
+    }
+}
+
+/// Brings the board to the `position` controlled by the host.
+fn follow(
+    position: &ControlledPosition,
+    starting_position: &str,
+    historical_board: &mut Signal<HistoricalBoard>,
+    move_builder: &mut Signal<MoveBuilder>,
+    puzzle: &mut Signal<Option<Puzzle>>,
+    premoves: &mut Signal<Premoves>,
+) {
+    match position.resolve(starting_position) {
+        Ok((start, moves)) => follow_line(
+            start,
+            &moves,
+            historical_board,
+            move_builder,
+            puzzle,
+            premoves,
+        ),
+        Err(err) => warn!("Controlled position {position:?} cannot be set: {err}"),
+    }
+}
+
+/// Brings the board to the position reached by the `moves` played from the `start` position.
+/// The game goes on if the position follows from the current one, otherwise the game is replaced.
+pub(crate) fn follow_line(
+    start: Board,
+    moves: &[Move],
+    historical_board: &mut Signal<HistoricalBoard>,
+    move_builder: &mut Signal<MoveBuilder>,
+    puzzle: &mut Signal<Option<Puzzle>>,
+    premoves: &mut Signal<Premoves>,
+) {
+    let caught_up = historical_board.write().catch_up(&start, moves);
+
+    match caught_up {
+        Ok(Some(0)) => {}
+        // A move being built or animated might not be legal in the new position.
+        Ok(Some(_)) => move_builder.set(MoveBuilder::new()),
+        Ok(None) => {
+            let move_tx = historical_board.peek().move_tx;
+
+            match HistoricalBoard::from_line(start, moves, move_tx) {
+                Ok(board) => {
+                    move_builder.set(MoveBuilder::new());
+                    replace_board(historical_board, premoves, board);
+                    puzzle.set(None);
+                }
+                Err(err) => warn!("Position cannot be set: {err}"),
+            }
+        }
+        Err(err) => warn!("Position cannot be reached: {err}"),
+    }
+}
+
+/// Replaces the game with a new one.
+/// Clocks of the new game start afresh with the time control of the replaced game.
+/// Premoves queued in the replaced game are dropped.
+fn replace_board(
+    historical_board: &mut Signal<HistoricalBoard>,
+    premoves: &mut Signal<Premoves>,
+    mut board: HistoricalBoard,
+) {
+    if !premoves.peek().is_empty() {
+        premoves.write().clear();
+    }
+
+    {
+        let replaced = historical_board.peek();
+        board.set_clocks(replaced.time_control(), replaced.clock_source());
+    }
+
+    historical_board.set(board);
+}
+
+/// Reports an [Action] which cannot be applied to the board.
+fn reject(historical_board: &HistoricalBoard, action: Action, error: HistoricalBoardError) {
+    if let Some(tx) = historical_board.move_tx.as_ref() {
+        tx.send(BoardAction::Rejected { action, error });
     }
 }
@@ -0,0 +1,40 @@
+use crate::chessboard::action::Action;
+use dioxus::prelude::*;
+use std::collections::VecDeque;
+
+/// Handle to a single [`crate::Chessboard`] instance.
+///
+/// Create it with [`use_chessboard`] and pass it to the board via the `controller` property.
+/// Actions sent through the handle reach exactly the board it is passed to.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ChessboardController {
+    /// Actions waiting to be processed by the board.
+    actions: Signal<VecDeque<Action>>,
+}
+
+impl ChessboardController {
+    /// Send an [`Action`] to the board.
+    /// Actions are processed in the order they are sent.
+    pub fn send(&self, action: Action) {
+        let mut actions = self.actions;
+        actions.write().push_back(action);
+    }
+
+    /// Takes all pending actions out of the queue.
+    pub(crate) fn take_actions(&self) -> Vec<Action> {
+        if self.actions.read().is_empty() {
+            return vec![];
+        }
+
+        let mut actions = self.actions;
+        let actions = actions.write().drain(..).collect();
+        actions
+    }
+}
+
+/// Creates a [`ChessboardController`] owned by the calling component.
+pub fn use_chessboard() -> ChessboardController {
+    let actions = use_signal(VecDeque::new);
+
+    ChessboardController { actions }
+}
@@ -1,10 +1,14 @@
 pub mod action;
 #[allow(clippy::module_inception)]
 pub mod chessboard;
+mod controller;
 mod properties;
 mod san_move;
 
 pub use action::Action;
 pub use chessboard::Chessboard;
+pub use controller::{use_chessboard, ChessboardController};
 pub use properties::ChessboardProps;
+#[cfg(feature = "sync")]
+pub(crate) use san_move::ply;
 pub use san_move::SanMove;
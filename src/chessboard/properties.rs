@@ -1,8 +1,15 @@
-use crate::chessboard::action::Action;
+use crate::chessboard::controller::ChessboardController;
+use crate::clock::{ClockSourceHandle, TimeControl};
+use crate::controlled::ControlledPosition;
+use crate::engine::UciEngine;
 use crate::history::BoardAction;
+#[cfg(feature = "sync")]
+use crate::sync::BoardSync;
+use crate::theme::BoardTheme;
 use crate::{Color, PieceSet};
 use dioxus::prelude::*;
 use std::fmt::Debug;
+use std::time::Duration;
 
 /// [Chessboard] properties.
 #[derive(PartialEq, Props, Clone)]
@@ -11,11 +18,18 @@ pub struct ChessboardProps {
     /// If you only need to display a position, set this to false.
     /// By default, the board will be interactive.
     is_interactive: Option<bool>,
+    /// Does the board remain interactive once the game has ended, e.g., by checkmate?
+    /// By default, no moves can be made in a terminal position.
+    interactive_after_game_over: Option<bool>,
     /// [`Color`] the player plays for, i.e., pieces at the bottom.
     player_color: Color,
 
     /// In a single-player mode, the player will only be able to move pieces of the `player_color`.
     /// Otherwise, the board allows all moves.
+    ///
+    /// While the opponent is thinking, the player can queue premoves by clicking or dragging pieces.
+    /// Premoves are played once it is the player's turn, unless they are illegal,
+    /// in which case the queue is dropped. The secondary button cancels the queue.
     single_player_mode: Option<bool>,
 
     /// The starting position in FEN notation.
@@ -23,12 +37,82 @@ pub struct ChessboardProps {
     /// **IMPORTANT:** This value sets only the initial position.
     /// The chessboard component will not update if the user changes this starting position,
     /// because it initializes an internal state that remains immutable with respect to property changes.
-    /// To update the position of an existing component, use [`Action::set_position`].
+    /// To update the position of an existing component, use [`crate::Action::set_position`].
     starting_position: Option<String>,
+    /// Position controlled by the host, either in FEN notation or as moves played from the `starting_position`.
+    /// The board always renders the latest position of the signal.
+    /// Moves of the player are not applied but proposed to the host as [`BoardAction::MoveProposed`],
+    /// the host accepts a move by adding it to the position.
+    /// Actions loading another game, e.g., [`crate::Action::set_position`], only last until the position changes.
+    ///
+    /// **IMPORTANT:** The board is controlled from its creation, the signal cannot be attached later.
+    /// By default, the board is not controlled.
+    position: Option<Signal<ControlledPosition>>,
+    /// Key under which the game is saved in the local storage of the browser after every change.
+    /// A board with a saved game restores it when it is created, e.g., after the page is refreshed,
+    /// a corrupted or outdated saved game is replaced with the `starting_position`.
+    /// A controlled board starts in the position of the host regardless of the saved game.
+    /// By default, the game is not saved.
+    #[cfg(feature = "persist")]
+    persist_key: Option<String>,
+    /// Do moves of the player wait for a confirmation of the host, e.g., a game server?
+    /// A pending move is shown on the board and reported as [`BoardAction::MoveProposed`].
+    /// It is committed with [`crate::Action::confirm_move`], and rolled back with [`crate::Action::reject_move`]
+    /// or once this timeout passes. The timeout is measured by the `clock_source`.
+    /// Moves of a controlled board or a puzzle do not wait for a confirmation.
+    /// By default, moves are committed right away.
+    confirmation_timeout: Option<Duration>,
     /// Pieces set.
     pieces_set: Option<PieceSet>,
-    /// Injected action.
-    action: Option<Action>,
+    /// Duration of the piece animations. Zero makes moves instant.
+    /// Animations are always disabled if the user prefers reduced motion.
+    /// By default, pieces move for half a second.
+    animation_duration: Option<Duration>,
+    /// Colors, piece scale and texture of this board.
+    /// By default, the look is defined by the board stylesheet.
+    theme: Option<BoardTheme>,
+    /// Can pieces be dragged with a mouse or touch?
+    /// Pieces can always be moved by clicking the source and then the destination square.
+    /// By default, dragging is enabled.
+    draggable: Option<bool>,
+    /// Are legal moves of the selected piece shown?
+    /// Empty target squares are marked with dots, captures are marked with rings.
+    /// By default, hints are shown.
+    show_move_hints: Option<bool>,
+    /// Is the board in the editor mode?
+    /// In the editor mode, pieces can be placed from the spare pieces, moved and removed with a right click,
+    /// the side to move, castling rights and en passant square can be set.
+    /// Every change resulting in a legal position is reported as [`BoardAction::PositionEdited`].
+    /// When the editor mode is switched off, the game starts from the edited position if it is legal.
+    /// Otherwise, the edits are discarded, which is reported as [`BoardAction::Rejected`]
+    /// of [`crate::Action::set_position`] with the edited position.
+    /// Actions sent via the `controller` while the position is edited are applied after the editor mode is left.
+    /// By default, the editor mode is off.
+    editor_mode: Option<bool>,
+    /// Time control of the game played on the board. The clock of the side to move starts immediately.
+    /// Clocks restart whenever a new position or game is loaded.
+    /// By default, the game is played without clocks.
+    time_control: Option<TimeControl>,
+    /// Source of time for the clocks, e.g., [`crate::ManualClock`] in tests.
+    /// By default, the system clock is used.
+    #[props(into)]
+    clock_source: Option<ClockSourceHandle>,
+    /// UCI engine analysing the current position, its results are sent to `san_tx`.
+    ///
+    /// **IMPORTANT:** The engine is attached to the board when it is created,
+    /// replacing it later has no effect.
+    engine: Option<UciEngine>,
+    /// Connection sharing the game with a peer, e.g., the board of the opponent in an online game.
+    ///
+    /// **IMPORTANT:** The connection is attached to the board when it is created,
+    /// replacing it later has no effect.
+    #[cfg(feature = "sync")]
+    sync: Option<BoardSync>,
+    /// Handle to send actions to the board, see [`crate::use_chessboard`].
+    ///
+    /// **IMPORTANT:** The controller is bound to the board when it is created
+    /// and must remain the same during the board's lifetime.
+    controller: Option<ChessboardController>,
     /// Transmitter channel of moves made on the board.
     san_tx: Option<Coroutine<BoardAction>>,
 }
@@ -37,14 +121,31 @@ impl ChessboardProps {
     pub(crate) fn complete(self) -> CompleteChessboardProps {
         CompleteChessboardProps {
             is_interactive: self.is_interactive.unwrap_or(true),
+            interactive_after_game_over: self.interactive_after_game_over.unwrap_or_default(),
             color: self.player_color,
             // By default, allow exploration mode.
             single_player_mode: self.single_player_mode.unwrap_or_default(),
             starting_position: self
                 .starting_position
                 .unwrap_or_else(|| Self::default_position().to_string()),
+            position: self.position,
+            #[cfg(feature = "persist")]
+            persist_key: self.persist_key,
+            confirmation_timeout: self.confirmation_timeout,
             pieces_set: self.pieces_set.unwrap_or(PieceSet::Standard),
-            action: self.action,
+            animation_duration: self
+                .animation_duration
+                .unwrap_or(Duration::from_millis(500)),
+            theme: self.theme.unwrap_or_default(),
+            is_draggable: self.draggable.unwrap_or(true),
+            show_move_hints: self.show_move_hints.unwrap_or(true),
+            editor_mode: self.editor_mode.unwrap_or_default(),
+            time_control: self.time_control,
+            clock_source: self.clock_source,
+            engine: self.engine,
+            #[cfg(feature = "sync")]
+            sync: self.sync,
+            controller: self.controller,
             san_tx: self.san_tx,
         }
     }
@@ -56,24 +157,57 @@ impl ChessboardProps {
 /// Complete properties with absent optional values of [`ChessboardProps`] filled with default values.
 pub struct CompleteChessboardProps {
     pub is_interactive: bool,
+    pub interactive_after_game_over: bool,
     pub color: Color,
     pub single_player_mode: bool,
     /// Starting position in FEN notation.
     pub starting_position: String,
+    pub position: Option<Signal<ControlledPosition>>,
+    #[cfg(feature = "persist")]
+    pub persist_key: Option<String>,
+    pub confirmation_timeout: Option<Duration>,
     pub pieces_set: PieceSet,
-    pub action: Option<Action>,
+    pub animation_duration: Duration,
+    pub theme: BoardTheme,
+    pub is_draggable: bool,
+    pub show_move_hints: bool,
+    pub editor_mode: bool,
+    pub time_control: Option<TimeControl>,
+    pub clock_source: Option<ClockSourceHandle>,
+    pub engine: Option<UciEngine>,
+    #[cfg(feature = "sync")]
+    pub sync: Option<BoardSync>,
+    pub controller: Option<ChessboardController>,
     pub san_tx: Option<Coroutine<BoardAction>>,
 }
 
 impl Debug for CompleteChessboardProps {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        f.debug_struct("CompleteChessboardProps")
+        let mut debug = f.debug_struct("CompleteChessboardProps");
+        debug
             .field("is_interactive", &self.is_interactive)
+            .field(
+                "interactive_after_game_over",
+                &self.interactive_after_game_over,
+            )
             .field("color", &self.color)
             .field("single_player_mode", &self.single_player_mode)
             .field("starting position", &self.starting_position)
+            .field("position", &self.position);
+        #[cfg(feature = "persist")]
+        debug.field("persist_key", &self.persist_key);
+        debug
+            .field("confirmation_timeout", &self.confirmation_timeout)
             .field("pieces_set", &self.pieces_set)
-            .field("action", &self.action)
-            .finish()
+            .field("animation_duration", &self.animation_duration)
+            .field("theme", &self.theme)
+            .field("is_draggable", &self.is_draggable)
+            .field("show_move_hints", &self.show_move_hints)
+            .field("editor_mode", &self.editor_mode)
+            .field("time_control", &self.time_control)
+            .field("engine", &self.engine);
+        #[cfg(feature = "sync")]
+        debug.field("sync", &self.sync);
+        debug.field("controller", &self.controller).finish()
     }
 }
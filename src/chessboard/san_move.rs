@@ -1,26 +1,147 @@
 use crate::Color;
+use owlchess::moves::Style;
+use owlchess::{Board, Coord, Move, MoveKind, Piece};
 use std::fmt::Display;
+use std::time::Duration;
 
-/// SAN-encoded chess move.
+/// SAN-encoded chess move together with the details of its application.
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct SanMove {
     pub san_repr: String,
-    pub piece: owlchess::Piece,
+    /// Move in the UCI notation, e.g., `e2e4` or `e7e8q`.
+    pub uci: String,
+    pub piece: Piece,
     pub color: Color,
+    pub src: Coord,
+    pub dst: Coord,
+    /// Piece captured by the move, including a pawn captured en passant.
+    pub captured: Option<Piece>,
+    /// Piece a pawn is promoted to.
+    pub promotion: Option<Piece>,
+    /// Does the move give check?
+    pub is_check: bool,
+    /// Does the move give checkmate?
+    pub is_mate: bool,
+    /// Position after the move in FEN notation.
+    pub fen: String,
+    /// Number of the half-move in the game, starting from 1 for the first move of White.
+    /// It is derived from the move number of the position, so it also accounts for
+    /// the moves played before a custom starting position.
+    pub ply: usize,
+    /// Remaining time of the side which has played the move, as stored when the move was made.
+    /// It is only present if the game is played with clocks.
+    pub clock: Option<Duration>,
 }
 
 impl SanMove {
-    pub fn new(san_repr: &str, piece: owlchess::Piece, color: Color) -> Self {
+    /// Creates a move knowing only its SAN representation, the piece and its color.
+    /// The other details are left empty, and both squares are set to a8.
+    #[deprecated(note = "use `SanMove::from_move`, which fills in all details of the move")]
+    pub fn new(san_repr: &str, piece: Piece, color: Color) -> Self {
         Self {
             san_repr: san_repr.to_string(),
+            uci: String::new(),
             piece,
             color,
+            src: Coord::from_index(0),
+            dst: Coord::from_index(0),
+            captured: None,
+            promotion: None,
+            is_check: false,
+            is_mate: false,
+            fen: String::new(),
+            ply: 0,
+            clock: None,
+        }
+    }
+
+    /// Describes a [`Move`] applied to the [`Board`].
+    ///
+    /// # Panics
+    /// The move must be legal on the board.
+    pub fn from_move(board: &Board, m: Move) -> Self {
+        let src_cell = board.get(m.src());
+
+        let piece = src_cell
+            .piece()
+            .expect("Move is valid, thus src must contain a piece");
+
+        let color = src_cell
+            .color()
+            .expect("Move is valid, thus src must contain a piece");
+
+        let captured = match m.kind() {
+            MoveKind::Enpassant => Some(Piece::Pawn),
+            _ => board.get(m.dst()).piece(),
+        };
+
+        let next = board.make_move(m).expect("Move must be valid");
+        let is_check = next.is_check();
+        let is_mate = is_check && !next.has_legal_moves();
+
+        Self {
+            san_repr: m
+                .styled(board, Style::San)
+                .expect("Board and move form a valid step")
+                .to_string(),
+            uci: m.to_string(),
+            piece,
+            color,
+            src: m.src(),
+            dst: m.dst(),
+            captured,
+            promotion: m.kind().promote(),
+            is_check,
+            is_mate,
+            fen: next.as_fen(),
+            ply: ply(board),
+            clock: None,
         }
     }
 }
 
+/// Number of the half-move to be played on the `board`, starting from 1 for the first move of White.
+/// A full-move number of 0, which some FENs carry, counts as 1.
+pub(crate) fn ply(board: &Board) -> usize {
+    let raw = board.raw();
+
+    (usize::from(raw.move_number.max(1)) - 1) * 2
+        + match raw.side {
+            Color::White => 1,
+            Color::Black => 2,
+        }
+}
+
 impl Display for SanMove {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(f, "{self:?}")
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::str::FromStr;
+
+    fn board(fen: &str) -> Board {
+        Board::from_str(fen).expect("FEN is valid")
+    }
+
+    #[test]
+    fn ply_counts_half_moves_from_the_first_move_of_white() {
+        assert_eq!(ply(&Board::initial()), 1);
+        assert_eq!(
+            ply(&board(
+                "rnbqkbnr/pppppppp/8/8/4P3/8/PPPP1PPP/RNBQKBNR b KQkq - 0 1"
+            )),
+            2
+        );
+        assert_eq!(ply(&board("4k3/8/8/8/8/8/8/4K3 w - - 0 10")), 19);
+    }
+
+    #[test]
+    fn ply_of_full_move_number_zero_does_not_underflow() {
+        assert_eq!(ply(&board("4k3/8/8/8/8/8/8/4K3 w - - 0 0")), 1);
+        assert_eq!(ply(&board("4k3/8/8/8/8/8/8/4K3 b - - 0 0")), 2);
+    }
+}
@@ -0,0 +1,260 @@
+use owlchess::Color;
+use std::time::Duration;
+
+/// Time control of a game: base time of each side plus a bonus per move.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TimeControl {
+    pub base: Duration,
+    pub bonus: Bonus,
+}
+
+/// Time added to the clock for every move.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Bonus {
+    None,
+    /// Fischer increment: the time is added to the clock after every move.
+    Increment(Duration),
+    /// Simple delay: the clock only starts running after the delay has elapsed.
+    Delay(Duration),
+}
+
+impl TimeControl {
+    /// Time control without any bonus.
+    pub fn sudden_death(base: Duration) -> Self {
+        Self {
+            base,
+            bonus: Bonus::None,
+        }
+    }
+
+    pub fn increment(base: Duration, increment: Duration) -> Self {
+        Self {
+            base,
+            bonus: Bonus::Increment(increment),
+        }
+    }
+
+    pub fn delay(base: Duration, delay: Duration) -> Self {
+        Self {
+            base,
+            bonus: Bonus::Delay(delay),
+        }
+    }
+}
+
+/// Clocks of both sides.
+/// All operations take the current time of a [`crate::ClockSource`].
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct Clocks {
+    time_control: TimeControl,
+    /// Remaining time of White and Black at the moment their clocks were stopped last time.
+    remaining: [Duration; 2],
+    /// Side whose clock is running and the time its turn has started.
+    running: Option<(Color, Duration)>,
+    /// Side which has run out of time.
+    flagged: Option<Color>,
+}
+
+impl Clocks {
+    /// Creates clocks and starts the clock of the `side` to move.
+    pub(crate) fn new(time_control: TimeControl, side: Color, now: Duration) -> Self {
+        Self {
+            time_control,
+            remaining: [time_control.base; 2],
+            running: Some((side, now)),
+            flagged: None,
+        }
+    }
+
+    pub(crate) fn time_control(&self) -> TimeControl {
+        self.time_control
+    }
+
+    /// Remaining time of the `side`.
+    pub(crate) fn remaining(&self, side: Color, now: Duration) -> Duration {
+        let stored = self.remaining[index(side)];
+
+        match self.running {
+            Some((running, started)) if running == side => {
+                let elapsed = now.saturating_sub(started);
+                let charged = match self.time_control.bonus {
+                    Bonus::Delay(delay) => elapsed.saturating_sub(delay),
+                    Bonus::None | Bonus::Increment(_) => elapsed,
+                };
+
+                stored.saturating_sub(charged)
+            }
+            _ => stored,
+        }
+    }
+
+    /// Side whose clock is running.
+    pub(crate) fn running(&self) -> Option<Color> {
+        self.running.map(|(side, _)| side)
+    }
+
+    pub(crate) fn flagged(&self) -> Option<Color> {
+        self.flagged
+    }
+
+    /// Checks whether the running side has run out of time.
+    /// The clocks stop once a flag falls.
+    /// Returns the side which has newly run out of time.
+    pub(crate) fn check_flag(&mut self, now: Duration) -> Option<Color> {
+        let side = self.running()?;
+
+        if !self.remaining(side, now).is_zero() {
+            return None;
+        }
+
+        self.stop(now);
+        self.flagged = Some(side);
+
+        Some(side)
+    }
+
+    /// Stops the clocks after the `side` has moved and starts the clock of its opponent.
+    /// Time elapsed on a clock of the other side is charged to it, e.g.,
+    /// when a move is played in a position the game has stepped back to.
+    /// Returns the remaining time of the side which has moved.
+    pub(crate) fn switch(&mut self, side: Color, now: Duration) -> Option<Duration> {
+        self.running?;
+        self.stop(now);
+
+        let remaining = &mut self.remaining[index(side)];
+        if let Bonus::Increment(increment) = self.time_control.bonus {
+            *remaining += increment;
+        }
+
+        let remaining = *remaining;
+        self.running = Some((side.inv(), now));
+
+        Some(remaining)
+    }
+
+    /// Takes back the move of the `side` which has left it `remaining` time,
+    /// and starts the clock of the `side` again.
+    /// The increment gained by the move is taken back as well.
+    pub(crate) fn take_back(&mut self, side: Color, remaining: Duration, now: Duration) {
+        if self.flagged.is_some() {
+            return;
+        }
+
+        self.stop(now);
+
+        self.remaining[index(side)] = match self.time_control.bonus {
+            Bonus::Increment(increment) => remaining.saturating_sub(increment),
+            Bonus::None | Bonus::Delay(_) => remaining,
+        };
+        self.running = Some((side, now));
+    }
+
+    /// Stops the running clock.
+    pub(crate) fn stop(&mut self, now: Duration) {
+        if let Some(side) = self.running() {
+            self.remaining[index(side)] = self.remaining(side, now);
+            self.running = None;
+        }
+    }
+}
+
+fn index(side: Color) -> usize {
+    match side {
+        Color::White => 0,
+        Color::Black => 1,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::clock::{ClockSource, ManualClock};
+
+    const MINUTE: Duration = Duration::from_secs(60);
+    const SECOND: Duration = Duration::from_secs(1);
+
+    fn clocks(time_control: TimeControl) -> (Clocks, ManualClock) {
+        let source = ManualClock::new();
+        (
+            Clocks::new(time_control, Color::White, source.now()),
+            source,
+        )
+    }
+
+    #[test]
+    fn switch_charges_the_side_which_has_moved() {
+        let (mut clocks, source) = clocks(TimeControl::sudden_death(MINUTE));
+
+        source.advance(10 * SECOND);
+        assert_eq!(clocks.switch(Color::White, source.now()), Some(50 * SECOND));
+        assert_eq!(clocks.running(), Some(Color::Black));
+
+        source.advance(5 * SECOND);
+        assert_eq!(clocks.remaining(Color::White, source.now()), 50 * SECOND);
+        assert_eq!(clocks.remaining(Color::Black, source.now()), 55 * SECOND);
+    }
+
+    #[test]
+    fn switch_starts_the_clock_of_the_opponent_of_the_side_which_has_moved() {
+        let (mut clocks, source) = clocks(TimeControl::sudden_death(MINUTE));
+
+        // White's clock runs, but Black moves in a position the game has stepped back to.
+        source.advance(10 * SECOND);
+        assert_eq!(clocks.switch(Color::Black, source.now()), Some(MINUTE));
+        assert_eq!(clocks.running(), Some(Color::White));
+        assert_eq!(clocks.remaining(Color::White, source.now()), 50 * SECOND);
+    }
+
+    #[test]
+    fn increment_is_added_after_every_move() {
+        let (mut clocks, source) = clocks(TimeControl::increment(MINUTE, 2 * SECOND));
+
+        source.advance(10 * SECOND);
+        assert_eq!(clocks.switch(Color::White, source.now()), Some(52 * SECOND));
+
+        source.advance(SECOND);
+        assert_eq!(clocks.switch(Color::Black, source.now()), Some(61 * SECOND));
+    }
+
+    #[test]
+    fn delay_elapses_before_the_clock_runs() {
+        let (mut clocks, source) = clocks(TimeControl::delay(MINUTE, 5 * SECOND));
+
+        source.advance(3 * SECOND);
+        assert_eq!(clocks.remaining(Color::White, source.now()), MINUTE);
+
+        source.advance(4 * SECOND);
+        assert_eq!(clocks.remaining(Color::White, source.now()), 58 * SECOND);
+        assert_eq!(clocks.switch(Color::White, source.now()), Some(58 * SECOND));
+    }
+
+    #[test]
+    fn flag_falls_when_the_time_runs_out() {
+        let (mut clocks, source) = clocks(TimeControl::sudden_death(MINUTE));
+
+        source.advance(59 * SECOND);
+        assert_eq!(clocks.check_flag(source.now()), None);
+
+        source.advance(2 * SECOND);
+        assert_eq!(clocks.check_flag(source.now()), Some(Color::White));
+        assert_eq!(clocks.flagged(), Some(Color::White));
+        assert_eq!(clocks.running(), None);
+        assert_eq!(clocks.switch(Color::White, source.now()), None);
+    }
+
+    #[test]
+    fn take_back_restores_the_time_before_the_move() {
+        let (mut clocks, source) = clocks(TimeControl::increment(MINUTE, 2 * SECOND));
+
+        source.advance(10 * SECOND);
+        let remaining = clocks
+            .switch(Color::White, source.now())
+            .expect("Clocks run");
+
+        source.advance(5 * SECOND);
+        clocks.take_back(Color::White, remaining, source.now());
+        assert_eq!(clocks.running(), Some(Color::White));
+        assert_eq!(clocks.remaining(Color::White, source.now()), 50 * SECOND);
+        assert_eq!(clocks.remaining(Color::Black, source.now()), 55 * SECOND);
+    }
+}
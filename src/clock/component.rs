@@ -0,0 +1,82 @@
+use crate::history::HistoricalBoard;
+use dioxus::prelude::*;
+use futures_timer::Delay;
+use owlchess::Color;
+use std::time::Duration;
+
+/// Interval between updates of the running clock.
+const TICK: Duration = Duration::from_millis(100);
+
+/// Component rendering the clocks of both sides: the opponent's one first, the player's one second.
+#[component]
+pub(crate) fn Clock(props: ClockProps) -> Element {
+    let mut historical_board = use_context::<Signal<HistoricalBoard>>();
+    let mut tick = use_signal(|| 0_u64);
+
+    use_future(move || async move {
+        loop {
+            Delay::new(TICK).await;
+
+            if historical_board.peek().is_flag_fallen() {
+                historical_board.write().check_flag();
+            }
+
+            // Only the running clock needs to be redrawn.
+            if historical_board.peek().running_clock().is_some() {
+                tick += 1;
+            }
+        }
+    });
+
+    // Subscribe to the ticks.
+    let _ = tick.read();
+
+    let board = historical_board.read();
+    let running = board.running_clock();
+    let flagged = board.flagged();
+
+    let clocks = [props.color.inv(), props.color].map(|side| {
+        let mut classes = vec!["clock"];
+        if running == Some(side) {
+            classes.push("running");
+        }
+        if flagged == Some(side) {
+            classes.push("flagged");
+        }
+
+        let remaining = board.remaining_time(side).map(format_time);
+
+        (side, classes.join(" "), remaining)
+    });
+
+    rsx! {
+        div { class: "clocks",
+            for (side , class , remaining) in clocks {
+                if let Some(remaining) = remaining {
+                    div { class, title: side.as_long_str(), "{remaining}" }
+                }
+            }
+        }
+    }
+}
+
+/// Formats the remaining time as `h:mm:ss`, `m:ss` or, under 10 seconds, `m:ss.d`.
+fn format_time(time: Duration) -> String {
+    let seconds = time.as_secs();
+    let (hours, minutes, seconds) = (seconds / 3600, seconds / 60 % 60, seconds % 60);
+
+    if hours > 0 {
+        format!("{hours}:{minutes:02}:{seconds:02}")
+    } else if time < Duration::from_secs(10) {
+        let tenths = time.subsec_millis() / 100;
+        format!("{minutes}:{seconds:02}.{tenths}")
+    } else {
+        format!("{minutes}:{seconds:02}")
+    }
+}
+
+#[derive(Props, Debug, PartialEq, Clone)]
+pub(crate) struct ClockProps {
+    /// [`Color`] the player plays for.
+    color: Color,
+}
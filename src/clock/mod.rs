@@ -0,0 +1,8 @@
+mod clocks;
+mod component;
+mod source;
+
+pub(crate) use clocks::Clocks;
+pub use clocks::{Bonus, TimeControl};
+pub(crate) use component::Clock;
+pub use source::{ClockSource, ClockSourceHandle, ManualClock, SystemClock};
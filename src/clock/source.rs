@@ -0,0 +1,104 @@
+use std::cell::Cell;
+use std::fmt::Debug;
+use std::rc::Rc;
+use std::time::Duration;
+use web_time::Instant;
+
+/// Source of time for chess clocks.
+///
+/// Only differences between the returned values are used, so the origin can be arbitrary,
+/// but it must never go backwards.
+pub trait ClockSource {
+    /// Current time measured from the origin of the source.
+    fn now(&self) -> Duration;
+}
+
+/// [`ClockSource`] based on the system monotonic clock.
+#[derive(Debug, Clone, Copy)]
+pub struct SystemClock {
+    origin: Instant,
+}
+
+impl SystemClock {
+    pub fn new() -> Self {
+        Self {
+            origin: Instant::now(),
+        }
+    }
+}
+
+impl Default for SystemClock {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ClockSource for SystemClock {
+    fn now(&self) -> Duration {
+        self.origin.elapsed()
+    }
+}
+
+/// [`ClockSource`] which only advances when told to, e.g., in tests.
+/// Clones share the same time.
+#[derive(Debug, Clone, Default)]
+pub struct ManualClock {
+    now: Rc<Cell<Duration>>,
+}
+
+impl ManualClock {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn advance(&self, duration: Duration) {
+        self.now.set(self.now.get() + duration);
+    }
+}
+
+impl ClockSource for ManualClock {
+    fn now(&self) -> Duration {
+        self.now.get()
+    }
+}
+
+/// Shared [`ClockSource`] which can be passed to [`crate::Chessboard`] as a property.
+/// Handles are equal if they refer to the same source.
+#[derive(Clone)]
+pub struct ClockSourceHandle(Rc<dyn ClockSource>);
+
+impl ClockSourceHandle {
+    pub fn new(source: impl ClockSource + 'static) -> Self {
+        Self(Rc::new(source))
+    }
+
+    pub(crate) fn now(&self) -> Duration {
+        self.0.now()
+    }
+}
+
+impl Default for ClockSourceHandle {
+    fn default() -> Self {
+        Self::new(SystemClock::new())
+    }
+}
+
+impl<S: ClockSource + 'static> From<S> for ClockSourceHandle {
+    fn from(source: S) -> Self {
+        Self::new(source)
+    }
+}
+
+impl PartialEq for ClockSourceHandle {
+    fn eq(&self, other: &Self) -> bool {
+        Rc::ptr_eq(&self.0, &other.0)
+    }
+}
+
+impl Debug for ClockSourceHandle {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("ClockSourceHandle")
+            .field(&self.now())
+            .finish()
+    }
+}
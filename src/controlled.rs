@@ -0,0 +1,42 @@
+use crate::history::HistoricalBoardError;
+use owlchess::{Board, Move};
+use std::str::FromStr;
+
+/// Position of a board controlled by the host, see the `position` property of [`crate::Chessboard`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum ControlledPosition {
+    /// Position in FEN notation, without any history.
+    Fen(String),
+    /// Moves played from the starting position of the board, encoded either in UCI or in SAN.
+    Moves(Vec<String>),
+}
+
+impl ControlledPosition {
+    /// Resolves the position into the starting [`Board`] and the [`Move`]s played from it.
+    /// `starting_position` in FEN notation is only used for [`ControlledPosition::Moves`].
+    pub(crate) fn resolve(
+        &self,
+        starting_position: &str,
+    ) -> Result<(Board, Vec<Move>), HistoricalBoardError> {
+        let (fen, moves) = match self {
+            Self::Fen(fen) => (fen.as_str(), &[][..]),
+            Self::Moves(moves) => (starting_position, &moves[..]),
+        };
+
+        let start = Board::from_str(fen)?;
+        let mut board = start.clone();
+        let mut line = vec![];
+
+        for m in moves {
+            let m = match Move::from_uci_legal(m, &board) {
+                Ok(m) => m,
+                Err(_) => Move::from_san(m, &board)?,
+            };
+
+            board = board.make_move(m)?;
+            line.push(m);
+        }
+
+        Ok((start, line))
+    }
+}
@@ -0,0 +1,66 @@
+use dioxus::html::geometry::{ClientPoint, PixelsRect};
+use owlchess::{Cell, Coord, File, Rank};
+
+/// Origin of a dragged piece.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum DragSource {
+    /// The piece is dragged from a square of the board.
+    Square(Coord),
+    /// A spare piece is dragged from the palette of the board editor.
+    Spare(Cell),
+}
+
+/// A piece dragged over the board with a pointer (mouse, pen or touch).
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct Drag {
+    /// Where the piece is dragged from.
+    pub src: DragSource,
+    /// Pointer position where dragging started.
+    origin: ClientPoint,
+    /// Current pointer position.
+    position: ClientPoint,
+    /// Releasing the piece on its own square deselects it.
+    /// This is the case when the piece had already been selected before dragging started.
+    pub deselect_on_release: bool,
+}
+
+impl Drag {
+    pub(crate) fn new(src: DragSource, origin: ClientPoint, deselect_on_release: bool) -> Self {
+        Self {
+            src,
+            origin,
+            position: origin,
+            deselect_on_release,
+        }
+    }
+
+    pub(crate) fn move_to(&mut self, position: ClientPoint) {
+        self.position = position;
+    }
+
+    /// Displacement of the pointer since dragging started, in pixels.
+    pub(crate) fn offset(&self) -> (f64, f64) {
+        (
+            self.position.x - self.origin.x,
+            self.position.y - self.origin.y,
+        )
+    }
+}
+
+/// Finds the square under the pointer at `position` on the board occupying `rect`.
+/// `files` and `ranks` are listed in the order they are displayed, i.e., left to right and top to bottom.
+pub(crate) fn coord_at(
+    position: ClientPoint,
+    rect: PixelsRect,
+    files: &[File],
+    ranks: &[Rank],
+) -> Option<Coord> {
+    if rect.is_empty() || !rect.contains(position.cast_unit()) {
+        return None;
+    }
+
+    let column = ((position.x - rect.min_x()) / rect.width() * files.len() as f64) as usize;
+    let row = ((position.y - rect.min_y()) / rect.height() * ranks.len() as f64) as usize;
+
+    Some(Coord::from_parts(*files.get(column)?, *ranks.get(row)?))
+}
@@ -0,0 +1,83 @@
+use crate::editor::{edit, EditedPosition};
+use crate::history::HistoricalBoard;
+use dioxus::prelude::*;
+use owlchess::{CastlingSide, Color, Coord};
+use std::str::FromStr;
+
+/// Component rendering the controls of the board editor:
+/// side to move, castling rights, en passant square and legality of the position.
+#[component]
+pub(crate) fn EditorControls() -> Element {
+    let historical_board = use_context::<Signal<HistoricalBoard>>();
+    let mut editor = use_context::<Signal<Option<EditedPosition>>>();
+
+    let Some(position) = editor.read().clone() else {
+        return rsx! {};
+    };
+
+    let castling = [
+        (Color::White, CastlingSide::King, "White O-O"),
+        (Color::White, CastlingSide::Queen, "White O-O-O"),
+        (Color::Black, CastlingSide::King, "Black O-O"),
+        (Color::Black, CastlingSide::Queen, "Black O-O-O"),
+    ];
+
+    let en_passant = position
+        .en_passant()
+        .map(|dst| dst.to_string())
+        .unwrap_or_else(|| "-".to_string());
+
+    rsx! {
+        div { class: "editor-controls",
+            label {
+                "Side to move "
+                select {
+                    value: match position.side() {
+                        Color::White => "w",
+                        Color::Black => "b",
+                    },
+                    onchange: move |ev| {
+                        let side = if ev.value() == "b" { Color::Black } else { Color::White };
+                        edit(&mut editor, &historical_board, |position| position.set_side(side));
+                    },
+                    option { value: "w", "White" }
+                    option { value: "b", "Black" }
+                }
+            }
+            for (color , side , name) in castling {
+                label {
+                    input {
+                        r#type: "checkbox",
+                        checked: position.castling(color, side),
+                        disabled: !position.is_castling_possible(color, side),
+                        onchange: move |ev| {
+                            edit(
+                                &mut editor,
+                                &historical_board,
+                                |position| position.set_castling(color, side, ev.checked()),
+                            );
+                        },
+                    }
+                    "{name}"
+                }
+            }
+            label {
+                "En passant "
+                select {
+                    value: en_passant,
+                    onchange: move |ev| {
+                        let dst = Coord::from_str(&ev.value()).ok();
+                        edit(&mut editor, &historical_board, |position| position.set_en_passant(dst));
+                    },
+                    option { value: "-", "-" }
+                    for dst in position.en_passant_squares() {
+                        option { value: "{dst}", "{dst}" }
+                    }
+                }
+            }
+            if let Err(err) = position.validate() {
+                div { class: "editor-error", "Illegal position: {err}" }
+            }
+        }
+    }
+}
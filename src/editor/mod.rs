@@ -0,0 +1,35 @@
+mod controls;
+mod palette;
+mod position;
+
+pub(crate) use controls::EditorControls;
+pub(crate) use palette::SparePieces;
+pub(crate) use position::EditedPosition;
+
+use crate::history::{BoardAction, HistoricalBoard};
+use dioxus::prelude::*;
+use tracing::debug;
+
+/// Applies a change to the position in the board editor.
+/// If the resulting position is legal, it is reported as [`BoardAction::PositionEdited`].
+pub(crate) fn edit(
+    editor: &mut Signal<Option<EditedPosition>>,
+    historical_board: &Signal<HistoricalBoard>,
+    change: impl FnOnce(&mut EditedPosition),
+) {
+    let mut editor = editor.write();
+    let Some(position) = editor.as_mut() else {
+        return;
+    };
+
+    change(position);
+
+    match position.validate() {
+        Ok(board) => {
+            if let Some(tx) = historical_board.read().move_tx.as_ref() {
+                tx.send(BoardAction::PositionEdited(board.as_fen()));
+            }
+        }
+        Err(err) => debug!("Edited position is not legal: {err}"),
+    }
+}
@@ -0,0 +1,64 @@
+use crate::drag::{Drag, DragSource};
+use crate::pieces::compute_piece_img_src;
+use crate::PieceSet;
+use dioxus::html::input_data::MouseButton;
+use dioxus::prelude::*;
+use owlchess::{Cell, Color, Piece};
+
+/// Component rendering spare pieces of one color, which can be dragged onto the board in the editor mode.
+#[component]
+pub(crate) fn SparePieces(props: SparePiecesProps) -> Element {
+    let mut drag = use_context::<Signal<Option<Drag>>>();
+
+    let pieces = [
+        Piece::King,
+        Piece::Queen,
+        Piece::Rook,
+        Piece::Bishop,
+        Piece::Knight,
+        Piece::Pawn,
+    ]
+    .map(|piece| {
+        let cell = Cell::from_parts(props.color, piece);
+        let img_src =
+            compute_piece_img_src(&props.pieces_set, cell).expect("Spare pieces occupy cells");
+        (cell, img_src)
+    });
+
+    rsx! {
+        div { class: "spare-pieces",
+            for (cell , img_src) in pieces {
+                div {
+                    img {
+                        src: img_src,
+                        class: "scaled",
+                        draggable: false,
+                        transform: drag
+                            .read()
+                            .filter(|drag| drag.src == DragSource::Spare(cell))
+                            .map(|drag| {
+                                let (x, y) = drag.offset();
+                                format!("translateX({x}px) translateY({y}px) scale(var(--piece-scale))")
+                            }),
+                        z_index: if drag.read().is_some_and(|drag| drag.src == DragSource::Spare(cell)) { "10000" },
+                        // Dragged piece must not hide the squares under it from the pointer.
+                        pointer_events: if drag.read().is_some_and(|drag| drag.src == DragSource::Spare(cell)) { "none" },
+                        onpointerdown: move |ev| {
+                            if ev.trigger_button() == Some(MouseButton::Primary) {
+                                drag.set(
+                                    Some(Drag::new(DragSource::Spare(cell), ev.client_coordinates(), false)),
+                                );
+                            }
+                        },
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[derive(Props, Debug, PartialEq, Clone)]
+pub(crate) struct SparePiecesProps {
+    color: Color,
+    pieces_set: PieceSet,
+}
@@ -0,0 +1,138 @@
+use crate::history::HistoricalBoardError;
+use owlchess::{Board, CastlingSide, Cell, Color, Coord, File, Piece, Rank, RawBoard};
+
+/// Position being set up in the board editor.
+/// Unlike [`Board`], it might be illegal, e.g., while kings are not placed yet.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct EditedPosition {
+    raw: RawBoard,
+}
+
+impl EditedPosition {
+    pub(crate) fn new(board: &Board) -> Self {
+        Self { raw: *board.raw() }
+    }
+
+    pub(crate) fn get(&self, coord: Coord) -> Cell {
+        self.raw.get(coord)
+    }
+
+    pub(crate) fn put(&mut self, coord: Coord, cell: Cell) {
+        self.raw.put(coord, cell);
+    }
+
+    pub(crate) fn remove(&mut self, coord: Coord) {
+        self.raw.put(coord, Cell::EMPTY);
+    }
+
+    /// Moves a piece to another square replacing its content.
+    pub(crate) fn move_piece(&mut self, src: Coord, dst: Coord) {
+        if src == dst {
+            return;
+        }
+
+        let cell = self.raw.get(src);
+        self.raw.put(src, Cell::EMPTY);
+        self.raw.put(dst, cell);
+    }
+
+    pub(crate) fn side(&self) -> Color {
+        self.raw.side
+    }
+
+    /// Sets the side to move.
+    /// En passant capture is reset, because it is only possible for the opponent of the side which has just moved.
+    pub(crate) fn set_side(&mut self, side: Color) {
+        self.raw.side = side;
+        self.raw.ep_source = None;
+    }
+
+    /// Are the king and the rook on their initial squares, so that castling rights can be granted?
+    pub(crate) fn is_castling_possible(&self, color: Color, side: CastlingSide) -> bool {
+        let rank = match color {
+            Color::White => Rank::R1,
+            Color::Black => Rank::R8,
+        };
+        let rook_file = match side {
+            CastlingSide::King => File::H,
+            CastlingSide::Queen => File::A,
+        };
+
+        self.raw.get2(File::E, rank) == Cell::from_parts(color, Piece::King)
+            && self.raw.get2(rook_file, rank) == Cell::from_parts(color, Piece::Rook)
+    }
+
+    /// Castling rights which are granted and can be exercised with the current placement of pieces.
+    pub(crate) fn castling(&self, color: Color, side: CastlingSide) -> bool {
+        self.raw.castling.has(color, side) && self.is_castling_possible(color, side)
+    }
+
+    pub(crate) fn set_castling(&mut self, color: Color, side: CastlingSide, is_allowed: bool) {
+        if is_allowed {
+            self.raw.castling.set(color, side);
+        } else {
+            self.raw.castling.unset(color, side);
+        }
+    }
+
+    /// Squares a pawn can be captured en passant on.
+    /// A pawn of the side which has just moved must be able to have made a double step,
+    /// i.e., the square it has skipped and its starting square are empty.
+    pub(crate) fn en_passant_squares(&self) -> Vec<Coord> {
+        let (pawn_rank, dst_rank, origin_rank) = match self.raw.side {
+            Color::White => (Rank::R5, Rank::R6, Rank::R7),
+            Color::Black => (Rank::R4, Rank::R3, Rank::R2),
+        };
+        let pawn = Cell::from_parts(self.raw.side.inv(), Piece::Pawn);
+
+        File::iter()
+            .filter(|&f| {
+                self.raw.get2(f, pawn_rank) == pawn
+                    && self.raw.get2(f, dst_rank).is_free()
+                    && self.raw.get2(f, origin_rank).is_free()
+            })
+            .map(|f| Coord::from_parts(f, dst_rank))
+            .collect()
+    }
+
+    /// Square a pawn can be captured en passant on, if such capture is allowed.
+    pub(crate) fn en_passant(&self) -> Option<Coord> {
+        self.raw
+            .ep_dest()
+            .filter(|dst| self.en_passant_squares().contains(dst))
+    }
+
+    pub(crate) fn set_en_passant(&mut self, dst: Option<Coord>) {
+        let pawn_rank = match self.raw.side {
+            Color::White => Rank::R5,
+            Color::Black => Rank::R4,
+        };
+
+        self.raw.ep_source = dst.map(|dst| Coord::from_parts(dst.file(), pawn_rank));
+    }
+
+    /// Position in FEN notation, even if it is not legal.
+    pub(crate) fn as_fen(&self) -> String {
+        self.raw.as_fen()
+    }
+
+    /// Checks that the position is legal.
+    /// Castling rights and en passant capture which cannot be exercised are dropped.
+    pub(crate) fn validate(&self) -> Result<Board, HistoricalBoardError> {
+        let mut raw = self.raw;
+
+        for color in [Color::White, Color::Black] {
+            for side in [CastlingSide::King, CastlingSide::Queen] {
+                if !self.castling(color, side) {
+                    raw.castling.unset(color, side);
+                }
+            }
+        }
+
+        if self.en_passant().is_none() {
+            raw.ep_source = None;
+        }
+
+        Ok(Board::try_from(raw)?)
+    }
+}
@@ -0,0 +1,135 @@
+use owlchess::moves::Style;
+use owlchess::{Board, Color, Move};
+use std::fmt::Display;
+
+/// Evaluation of a position from the point of view of White.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Evaluation {
+    /// Advantage in hundredths of a pawn, positive if White is better.
+    Centipawns(i32),
+    /// Number of moves to checkmate, positive if White mates.
+    Mate(i32),
+}
+
+impl Evaluation {
+    /// Converts a score reported from the point of view of the `side` to move to the one of White.
+    fn relative_to_white(self, side: Color) -> Self {
+        match (side, self) {
+            (Color::White, evaluation) => evaluation,
+            (Color::Black, Self::Centipawns(cp)) => Self::Centipawns(-cp),
+            (Color::Black, Self::Mate(moves)) => Self::Mate(-moves),
+        }
+    }
+}
+
+impl Display for Evaluation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Centipawns(cp) => write!(f, "{:+.2}", f64::from(*cp) / 100.0),
+            Self::Mate(moves) if *moves < 0 => write!(f, "-M{}", -moves),
+            Self::Mate(moves) => write!(f, "M{moves}"),
+        }
+    }
+}
+
+/// Engine analysis of the position on the board.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Analysis {
+    /// Search depth in plies.
+    pub depth: u32,
+    pub evaluation: Evaluation,
+    /// Principal variation: the best line found by the engine, SAN-encoded.
+    pub pv: Vec<String>,
+}
+
+impl Display for Analysis {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "depth {} eval {} pv {}",
+            self.depth,
+            self.evaluation,
+            self.pv.join(" ")
+        )
+    }
+}
+
+/// Line printed by a UCI engine, which is relevant to the analysis.
+#[derive(Debug, PartialEq)]
+pub(crate) enum EngineLine {
+    Info(Analysis),
+    /// UCI-encoded best move, absent if there are no legal moves.
+    BestMove(Option<String>),
+}
+
+impl EngineLine {
+    /// Parses a `line` of the engine analysing the `board` position.
+    /// `info` lines without a score or a principal variation are skipped.
+    pub(crate) fn parse(line: &str, board: &Board) -> Option<Self> {
+        let mut tokens = line.split_whitespace();
+
+        match tokens.next()? {
+            "info" => parse_info(tokens, board).map(Self::Info),
+            "bestmove" => Some(Self::BestMove(
+                tokens.next().filter(|&m| m != "(none)").map(str::to_string),
+            )),
+            _ => None,
+        }
+    }
+}
+
+/// Parses the tokens of an `info` line following the `info` keyword.
+fn parse_info<'a>(mut tokens: impl Iterator<Item = &'a str>, board: &Board) -> Option<Analysis> {
+    let mut depth = None;
+    let mut evaluation = None;
+    let mut pv = None;
+
+    while let Some(token) = tokens.next() {
+        match token {
+            "depth" => depth = tokens.next()?.parse().ok(),
+            "score" => {
+                evaluation = match (tokens.next()?, tokens.next()?.parse().ok()?) {
+                    ("cp", cp) => Some(Evaluation::Centipawns(cp)),
+                    ("mate", moves) => Some(Evaluation::Mate(moves)),
+                    _ => None,
+                }
+            }
+            // The principal variation is always the last item of the line.
+            "pv" => {
+                pv = Some(san_line(tokens, board));
+                break;
+            }
+            // The rest of the line is a free-form string.
+            "string" => break,
+            _ => {}
+        }
+    }
+
+    Some(Analysis {
+        depth: depth?,
+        evaluation: evaluation?.relative_to_white(board.side()),
+        pv: pv?,
+    })
+}
+
+/// Converts UCI-encoded moves played from the `board` position to SAN.
+/// The conversion stops at the first illegal move.
+fn san_line<'a>(moves: impl Iterator<Item = &'a str>, board: &Board) -> Vec<String> {
+    let mut board = board.clone();
+    let mut line = vec![];
+
+    for m in moves {
+        let Ok(m) = Move::from_uci_legal(m, &board) else {
+            break;
+        };
+
+        line.push(
+            m.styled(&board, Style::San)
+                .expect("Board and move form a valid step")
+                .to_string(),
+        );
+        board = board.make_move(m).expect("Move is legal");
+    }
+
+    line
+}
@@ -0,0 +1,395 @@
+use crate::engine::analysis::EngineLine;
+use crate::history::{BoardAction, HistoricalBoard};
+use crate::transport::{poll_periodically, LineTransport};
+use crate::SanMove;
+use dioxus::prelude::*;
+use owlchess::{Board, Move};
+use std::cell::RefCell;
+use std::fmt::Debug;
+use std::rc::Rc;
+use tracing::{debug, warn};
+
+/// Adapter of a UCI engine analysing the positions on a [`crate::Chessboard`],
+/// which exchanges UCI commands and replies with the engine over a [`LineTransport`].
+///
+/// The engine restarts the analysis whenever the current position changes.
+/// Commands are held back until the engine has completed the UCI handshake.
+/// Results are reported as [`BoardAction::Analysis`] and [`BoardAction::BestMove`].
+/// Clones refer to the same engine, handles are equal if they do.
+#[derive(Clone)]
+pub struct UciEngine(Rc<RefCell<EngineState>>);
+
+struct EngineState {
+    transport: Box<dyn LineTransport>,
+    /// Search depth limit, the search is infinite without it.
+    depth: Option<u32>,
+    /// Position being analysed and the `position` command describing it.
+    position: Option<(Board, String)>,
+    /// Number of searches which have been started, but not finished with `bestmove` yet.
+    /// Only the output of the last one is relevant.
+    searches: usize,
+    /// Stage of the UCI handshake, commands are queued until it is complete.
+    handshake: Handshake,
+    /// Commands waiting for the handshake to complete.
+    queue: Vec<String>,
+}
+
+/// Stages of the UCI handshake: `uci` is answered with `uciok`, then `isready` with `readyok`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Handshake {
+    Uci,
+    IsReady,
+    Done,
+}
+
+impl UciEngine {
+    /// Attaches the engine reachable via the `transport`, which searches infinitely.
+    pub fn new(transport: impl LineTransport + 'static) -> Self {
+        Self::with_limit(transport, None)
+    }
+
+    /// Attaches the engine reachable via the `transport`, which searches to the given `depth`.
+    pub fn with_depth(transport: impl LineTransport + 'static, depth: u32) -> Self {
+        Self::with_limit(transport, Some(depth))
+    }
+
+    fn with_limit(transport: impl LineTransport + 'static, depth: Option<u32>) -> Self {
+        let mut state = EngineState {
+            transport: Box::new(transport),
+            depth,
+            position: None,
+            searches: 0,
+            handshake: Handshake::Uci,
+            queue: vec![],
+        };
+        state.transmit("uci");
+
+        Self(Rc::new(RefCell::new(state)))
+    }
+
+    /// Starts analysing the position currently pointed to by the step pointer,
+    /// unless it is already being analysed.
+    /// The search of the previous position is stopped.
+    pub(crate) fn analyse(&self, historical_board: &HistoricalBoard) {
+        let command = historical_board.uci_position();
+        let mut state = self.0.borrow_mut();
+
+        if state
+            .position
+            .as_ref()
+            .is_some_and(|(_, analysed)| *analysed == command)
+        {
+            return;
+        }
+
+        if state.searches > 0 {
+            state.send("stop");
+        }
+
+        state.send(&command);
+        match state.depth {
+            Some(depth) => state.send(&format!("go depth {depth}")),
+            None => state.send("go infinite"),
+        }
+
+        state.searches += 1;
+        state.position = Some(((**historical_board).clone(), command));
+    }
+
+    /// Processes the lines printed by the engine since the last poll.
+    /// Returns the results of the analysis of the current position.
+    pub(crate) fn poll(&self) -> Vec<BoardAction> {
+        let mut state = self.0.borrow_mut();
+        let mut actions = vec![];
+
+        while let Some(line) = state.transport.try_receive() {
+            if state.handshake != Handshake::Done {
+                state.shake_hands(&line);
+                continue;
+            }
+
+            // Output of a stopped search is skipped.
+            if state.searches > 1 {
+                if line.starts_with("bestmove") {
+                    state.searches -= 1;
+                }
+                continue;
+            }
+
+            let Some((board, _)) = state.position.as_ref() else {
+                continue;
+            };
+
+            match EngineLine::parse(&line, board) {
+                Some(EngineLine::Info(analysis)) => actions.push(BoardAction::Analysis(analysis)),
+                Some(EngineLine::BestMove(m)) => {
+                    let best_move = m.and_then(|m| match Move::from_uci_legal(&m, board) {
+                        Ok(m) => Some(SanMove::from_move(board, m)),
+                        Err(err) => {
+                            warn!("Engine best move {m} is not legal: {err}");
+                            None
+                        }
+                    });
+
+                    actions.extend(best_move.map(BoardAction::BestMove));
+                    state.searches = state.searches.saturating_sub(1);
+                }
+                None => {}
+            }
+        }
+
+        actions
+    }
+}
+
+impl EngineState {
+    /// Sends the `command` to the engine, or queues it until the handshake is complete.
+    fn send(&mut self, command: &str) {
+        if self.handshake == Handshake::Done {
+            self.transmit(command);
+        } else {
+            self.queue.push(command.to_string());
+        }
+    }
+
+    fn transmit(&mut self, command: &str) {
+        if let Err(err) = self.transport.send(command) {
+            warn!("Command `{command}` cannot be sent to the engine: {err}");
+        }
+    }
+
+    /// Advances the handshake by the `line` printed by the engine.
+    /// Queued commands are sent once the engine is ready.
+    fn shake_hands(&mut self, line: &str) {
+        match (self.handshake, line.trim()) {
+            (Handshake::Uci, "uciok") => {
+                self.handshake = Handshake::IsReady;
+                self.transmit("isready");
+            }
+            (Handshake::IsReady, "readyok") => {
+                debug!("Engine is ready");
+                self.handshake = Handshake::Done;
+
+                for command in std::mem::take(&mut self.queue) {
+                    self.transmit(&command);
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+impl PartialEq for UciEngine {
+    fn eq(&self, other: &Self) -> bool {
+        Rc::ptr_eq(&self.0, &other.0)
+    }
+}
+
+impl Debug for UciEngine {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let state = self.0.borrow();
+
+        f.debug_struct("UciEngine")
+            .field("depth", &state.depth)
+            .field(
+                "position",
+                &state.position.as_ref().map(|(_, command)| command),
+            )
+            .finish()
+    }
+}
+
+/// Keeps the `engine` analysing the current position of the board in the context
+/// and reports its results to the channel of board actions.
+pub(crate) fn use_engine_analysis(engine: Option<UciEngine>) {
+    let historical_board = use_context::<Signal<HistoricalBoard>>();
+
+    let analysing = engine.clone();
+    use_effect(move || {
+        if let Some(engine) = analysing.as_ref() {
+            engine.analyse(&historical_board.read());
+        }
+    });
+
+    use_future(move || {
+        let engine = engine.clone();
+
+        async move {
+            let Some(engine) = engine else {
+                return;
+            };
+
+            poll_periodically(|| {
+                for action in engine.poll() {
+                    debug!("Engine reports: {action}");
+
+                    if let Some(tx) = historical_board.peek().move_tx.as_ref() {
+                        tx.send(action);
+                    }
+                }
+            })
+            .await;
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::{Analysis, Evaluation};
+    use std::collections::VecDeque;
+    use std::io;
+
+    /// Engine which replies with the lines pushed by the test.
+    #[derive(Clone, Default)]
+    struct ScriptedTransport {
+        sent: Rc<RefCell<Vec<String>>>,
+        replies: Rc<RefCell<VecDeque<String>>>,
+    }
+
+    impl ScriptedTransport {
+        fn reply(&self, lines: &[&str]) {
+            let mut replies = self.replies.borrow_mut();
+            replies.extend(lines.iter().map(|line| line.to_string()));
+        }
+
+        fn take_sent(&self) -> Vec<String> {
+            std::mem::take(&mut self.sent.borrow_mut())
+        }
+    }
+
+    impl LineTransport for ScriptedTransport {
+        fn send(&mut self, command: &str) -> io::Result<()> {
+            self.sent.borrow_mut().push(command.to_string());
+            Ok(())
+        }
+
+        fn try_receive(&mut self) -> Option<String> {
+            self.replies.borrow_mut().pop_front()
+        }
+    }
+
+    fn board(pgn: &str) -> HistoricalBoard {
+        HistoricalBoard::from_pgn(pgn, None).expect("PGN is valid")
+    }
+
+    fn ready_engine() -> (UciEngine, ScriptedTransport) {
+        let transport = ScriptedTransport::default();
+        let engine = UciEngine::with_depth(transport.clone(), 10);
+
+        transport.reply(&["uciok", "readyok"]);
+        engine.poll();
+        transport.take_sent();
+
+        (engine, transport)
+    }
+
+    #[test]
+    fn commands_wait_for_the_handshake() {
+        let transport = ScriptedTransport::default();
+        let engine = UciEngine::with_depth(transport.clone(), 10);
+
+        engine.analyse(&board("1. e4 *"));
+        assert_eq!(transport.take_sent(), ["uci"]);
+
+        transport.reply(&["id name Scripted", "uciok"]);
+        engine.poll();
+        assert_eq!(transport.take_sent(), ["isready"]);
+
+        transport.reply(&["readyok"]);
+        engine.poll();
+        assert_eq!(
+            transport.take_sent(),
+            [
+                "position fen rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1 moves e2e4",
+                "go depth 10",
+            ]
+        );
+    }
+
+    #[test]
+    fn output_of_stopped_searches_is_skipped() {
+        let (engine, transport) = ready_engine();
+
+        engine.analyse(&board("1. e4 *"));
+        engine.analyse(&board("1. d4 *"));
+        assert_eq!(transport.take_sent()[2], "stop");
+
+        transport.reply(&[
+            "info depth 5 score cp 30 pv e7e5",
+            "bestmove e7e5",
+            "info depth 1 score cp -20 pv d7d5",
+            "bestmove d7d5",
+        ]);
+
+        let actions = engine.poll();
+        assert_eq!(actions.len(), 2);
+        assert!(matches!(
+            &actions[0],
+            BoardAction::Analysis(Analysis { depth: 1, pv, .. }) if pv == &["d5"]
+        ));
+        assert!(matches!(
+            &actions[1],
+            BoardAction::BestMove(m) if m.san_repr == "d5"
+        ));
+    }
+
+    #[test]
+    fn info_score_is_relative_to_white() {
+        let board = Board::initial();
+        let line = EngineLine::parse("info depth 12 score cp 35 nodes 1000 pv e2e4 e7e5", &board);
+        assert_eq!(
+            line,
+            Some(EngineLine::Info(Analysis {
+                depth: 12,
+                evaluation: Evaluation::Centipawns(35),
+                pv: vec!["e4".to_string(), "e5".to_string()],
+            }))
+        );
+
+        let board = board
+            .make_move(Move::from_san("e4", &board).unwrap())
+            .unwrap();
+        let line = EngineLine::parse("info depth 3 score cp 35 pv g8f6", &board);
+        assert!(matches!(
+            line,
+            Some(EngineLine::Info(Analysis {
+                evaluation: Evaluation::Centipawns(-35),
+                ..
+            }))
+        ));
+
+        let line = EngineLine::parse("info depth 3 score mate 2 pv d8h4", &board);
+        assert!(matches!(
+            line,
+            Some(EngineLine::Info(Analysis {
+                evaluation: Evaluation::Mate(-2),
+                ..
+            }))
+        ));
+    }
+
+    #[test]
+    fn pv_stops_at_the_first_illegal_move() {
+        let board = Board::initial();
+        let line = EngineLine::parse("info depth 2 score mate 3 pv g1f3 e2e5 d7d5", &board);
+        assert!(matches!(
+            line,
+            Some(EngineLine::Info(Analysis { pv, .. })) if pv == ["Nf3"]
+        ));
+    }
+
+    #[test]
+    fn bestmove_without_legal_moves_is_absent() {
+        let board = Board::initial();
+        assert_eq!(
+            EngineLine::parse("bestmove (none)", &board),
+            Some(EngineLine::BestMove(None))
+        );
+        assert_eq!(
+            EngineLine::parse("bestmove e2e4 ponder e7e5", &board),
+            Some(EngineLine::BestMove(Some("e2e4".to_string())))
+        );
+        assert_eq!(EngineLine::parse("info string hello", &board), None);
+    }
+}
@@ -0,0 +1,11 @@
+mod analysis;
+#[allow(clippy::module_inception)]
+mod engine;
+#[cfg(feature = "desktop")]
+mod process;
+
+pub use analysis::{Analysis, Evaluation};
+pub(crate) use engine::use_engine_analysis;
+pub use engine::UciEngine;
+#[cfg(feature = "desktop")]
+pub use process::ProcessTransport;
@@ -0,0 +1,77 @@
+use crate::transport::LineTransport;
+use std::ffi::OsStr;
+use std::io::{self, BufRead, BufReader, Write};
+use std::process::{Child, ChildStdin, Command, Stdio};
+use std::sync::mpsc::{self, Receiver};
+use std::thread;
+use tracing::{debug, warn};
+
+/// [`LineTransport`] to a local UCI engine process.
+///
+/// Output of the engine is read on a separate thread, so receiving never blocks.
+/// The engine is asked to quit when the transport is dropped.
+pub struct ProcessTransport {
+    child: Child,
+    stdin: ChildStdin,
+    lines: Receiver<String>,
+}
+
+impl ProcessTransport {
+    /// Starts the engine executable found at the `path`.
+    pub fn spawn(path: impl AsRef<OsStr>) -> io::Result<Self> {
+        let mut child = Command::new(path)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::null())
+            .spawn()?;
+
+        let stdin = child.stdin.take().expect("Stdin is piped");
+        let stdout = child.stdout.take().expect("Stdout is piped");
+
+        let (tx, lines) = mpsc::channel();
+        thread::spawn(move || {
+            for line in BufReader::new(stdout).lines() {
+                let line = match line {
+                    Ok(line) => line,
+                    Err(err) => {
+                        warn!("Engine output cannot be read: {err}");
+                        break;
+                    }
+                };
+
+                // The transport has been dropped.
+                if tx.send(line).is_err() {
+                    break;
+                }
+            }
+        });
+
+        Ok(Self {
+            child,
+            stdin,
+            lines,
+        })
+    }
+}
+
+impl LineTransport for ProcessTransport {
+    fn send(&mut self, command: &str) -> io::Result<()> {
+        debug!("Engine <- {command}");
+        writeln!(self.stdin, "{command}")?;
+        self.stdin.flush()
+    }
+
+    fn try_receive(&mut self) -> Option<String> {
+        self.lines.try_recv().ok()
+    }
+}
+
+impl Drop for ProcessTransport {
+    fn drop(&mut self) {
+        let _ = self.send("quit");
+
+        // The engine might take a while to quit, so it is not waited for.
+        let _ = self.child.kill();
+        let _ = self.child.wait();
+    }
+}
@@ -19,16 +19,16 @@ pub(crate) fn Files(props: FilesProps) -> Element {
                         match props.color {
                             Color::White => {
                                 if file.index() % 2 == 0 {
-                                    "var(--color-light)"
+                                    "var(--color-coordinate-dark, var(--color-light))"
                                 } else {
-                                    "var(--color-dark)"
+                                    "var(--color-coordinate-light, var(--color-dark))"
                                 }
                             }
                             Color::Black => {
                                 if file.index() % 2 == 0 {
-                                    "var(--color-dark)"
+                                    "var(--color-coordinate-light, var(--color-dark))"
                                 } else {
-                                    "var(--color-light)"
+                                    "var(--color-coordinate-dark, var(--color-light))"
                                 }
                             }
                         },
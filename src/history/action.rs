@@ -1,4 +1,9 @@
-use crate::SanMove;
+use crate::engine::Analysis;
+#[cfg(feature = "serde")]
+use crate::history::BoardState;
+use crate::history::{HistoricalBoardError, Termination};
+use crate::{Action, SanMove, Shape};
+use owlchess::{Color, GameStatus};
 use std::fmt::Display;
 
 /// Description of navigation between the game steps on the [`crate::history::HistoricalBoard`]
@@ -7,6 +12,13 @@ use std::fmt::Display;
 pub enum BoardAction {
     /// Wraps an [`owlchess::Move`] that has been applied to the old board to get the new board.
     Apply(SanMove),
+    /// A move made by the player which is not applied until the host accepts it.
+    /// On a board controlled by the host, see [`crate::ControlledPosition`], the host accepts the move
+    /// by updating the position, or rejects it by leaving the position as is.
+    /// If moves require a confirmation, the move is pending until [`Action::confirm_move`] or [`Action::reject_move`].
+    MoveProposed(SanMove),
+    /// A pending move has not been confirmed in time and is rolled back.
+    MoveTimedOut(SanMove),
     /// In a historical sequence:
     /// Intermediate(board_1, move_1) -> Intermediate(board_2, move_2) -> Last(board_3),
     /// stepping back from intermediate step 2 yields the SAN move (move_**1**) which, when applied to board_1, produces board_2.
@@ -17,16 +29,76 @@ pub enum BoardAction {
     StepForward(SanMove),
     SetStartPosition,
     SetEndPosition,
+    /// Complete game history in PGN, sent in reply to [`crate::Action::export_pgn`].
+    Pgn(String),
+    /// Complete game, sent in reply to [`crate::Action::export_state`].
+    #[cfg(feature = "serde")]
+    State(Box<BoardState>),
+    /// Active variation path has changed.
+    /// The path lists the indices of the moves selected in every position starting from the first one,
+    /// index 0 is the main line, other indices are the variations in the order of their creation.
+    Variation(Vec<usize>),
+    /// The move has led to a terminal position.
+    GameOver {
+        /// Result of the game, [`GameStatus::Draw`] or the winning side.
+        outcome: GameStatus,
+        reason: Termination,
+    },
+    /// The side has offered a draw, which the opponent can accept with [`Action::accept_draw`].
+    DrawOffered(Color),
+    /// The puzzle loaded with [`Action::load_puzzle`] is solved.
+    PuzzleSolved,
+    /// A move played in the puzzle is wrong, the puzzle is over.
+    /// The wrong move is not applied to the board.
+    PuzzleFailed {
+        played: SanMove,
+        /// Move of the solution line.
+        expected: SanMove,
+    },
+    /// Analysis of the current position by the engine attached to the board, see [`crate::UciEngine`].
+    Analysis(Analysis),
+    /// The engine has finished the analysis of the current position with the best move.
+    BestMove(SanMove),
+    /// Shapes drawn over the board have been changed by the player.
+    /// All the shapes currently drawn are listed.
+    Shapes(Vec<Shape>),
+    /// Position set up in the board editor has changed and is legal.
+    /// The position is described in FEN notation.
+    PositionEdited(String),
+    /// An [`Action`] sent to the board cannot be applied, e.g., an illegal move or an invalid FEN.
+    /// An invalid starting position, as well as an illegal position left in the board editor,
+    /// is reported as a rejected [`Action::set_position`].
+    Rejected {
+        action: Action,
+        error: HistoricalBoardError,
+    },
 }
 
 impl Display for BoardAction {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
             Self::Apply(m) => write!(f, "Apply {m}"),
+            Self::MoveProposed(m) => write!(f, "Move proposed {m}"),
+            Self::MoveTimedOut(m) => write!(f, "Move timed out {m}"),
             Self::StepBack(m) => write!(f, "Step back {m}"),
             Self::StepForward(m) => write!(f, "Step forward {m}"),
             Self::SetStartPosition => write!(f, "Setting start position"),
             Self::SetEndPosition => write!(f, "Setting end position"),
+            Self::Pgn(pgn) => write!(f, "PGN\n{pgn}"),
+            #[cfg(feature = "serde")]
+            Self::State(state) => write!(f, "State {state:?}"),
+            Self::Variation(path) => write!(f, "Variation {path:?}"),
+            Self::GameOver { outcome, reason } => write!(f, "Game over {outcome} ({reason})"),
+            Self::DrawOffered(side) => write!(f, "Draw offered by {}", side.as_long_str()),
+            Self::PuzzleSolved => write!(f, "Puzzle solved"),
+            Self::PuzzleFailed { played, expected } => {
+                write!(f, "Puzzle failed: played {played}, expected {expected}")
+            }
+            Self::Analysis(analysis) => write!(f, "Analysis {analysis}"),
+            Self::BestMove(m) => write!(f, "Best move {m}"),
+            Self::Shapes(shapes) => write!(f, "Shapes {shapes:?}"),
+            Self::PositionEdited(fen) => write!(f, "Position edited {fen}"),
+            Self::Rejected { action, error } => write!(f, "Rejected {action:?}: {error}"),
         }
     }
 }
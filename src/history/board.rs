@@ -1,29 +1,48 @@
-use crate::history::BoardAction;
+use crate::clock::{ClockSourceHandle, Clocks, TimeControl};
+use crate::history::pgn::{write_pgn, Pgn, PgnMove};
+#[cfg(feature = "serde")]
+use crate::history::state::{Agreement, BoardState, MoveState};
+use crate::history::{BoardAction, Termination};
 use crate::SanMove;
+#[cfg(feature = "serde")]
+use crate::Shape;
 use dioxus::hooks::Coroutine;
-use owlchess::board::{FenParseError, PrettyStyle};
-use owlchess::moves::{Style, ValidateError};
-use owlchess::{Board, Color, Move};
+use owlchess::board::{self, FenParseError, PrettyStyle};
+use owlchess::moves::{san, uci, Style, ValidateError};
+use owlchess::types::OutcomeFilter;
+use owlchess::{Board, Color, GameStatus, Move};
 use std::fmt::Display;
 use std::ops::Deref;
 use std::str::FromStr;
+use std::time::Duration;
 use thiserror::Error;
 use tracing::debug;
 
 pub struct HistoricalBoard {
     /// When a move is successfully applied, it will be reported to this channel.
     pub(crate) move_tx: Option<Coroutine<BoardAction>>,
+    /// The starting position with all continuations played from it.
+    root: Node,
+    /// Active variation path: indices of the continuations selected in every position,
+    /// starting from the root.
+    path: Vec<usize>,
+    /// Number of moves along the active variation path leading to the current position.
+    /// INVARIANT: Step pointer never exceeds the length of the active variation path.
     step_pointer: usize,
-    /// A sequence of [`Board`]'s and associated [`Move`]'s which brings to the next version of [`Board`].
-    /// until the value in `board` is produced.
-    /// INVARIANT: Length of the history is at least 1.
-    history: Vec<Step>,
+    /// PGN tag pairs describing the game, e.g., `Event`, `White`, `Black`.
+    tags: Vec<(String, String)>,
+    /// Game termination marker.
+    result: GameStatus,
+    /// Clocks of both sides, if the game is played with a time control.
+    clocks: Option<Clocks>,
+    clock_source: ClockSourceHandle,
+    /// Game ended by the players rather than on the board, i.e., by a resignation or an agreed draw.
+    agreement: Option<(GameStatus, Termination)>,
+    /// Side which has offered a draw, the offer stands until the opponent accepts it or moves.
+    draw_offer: Option<Color>,
 }
 
 impl HistoricalBoard {
-    const INVARIANT_AT_LEAST_1_STEP: &'static str = "[BUG] History must contain at least 1 element";
-    const INVARIANT_LAST_BUT_1_IS_INTERMEDIATE: &'static str =
-        "[BUG] Last but one historical element must be intermediate step";
     /// Construct a new board from FEN notation.
     pub fn initialize(
         fen: &str,
@@ -32,77 +51,495 @@ impl HistoricalBoard {
         Board::from_str(fen)
             .map(|board| Self {
                 move_tx,
+                root: Node::new(board),
+                path: vec![],
                 step_pointer: 0,
-                history: vec![Step::Last(board)],
+                tags: vec![],
+                result: GameStatus::Running,
+                clocks: None,
+                clock_source: ClockSourceHandle::default(),
+                agreement: None,
+                draw_offer: None,
             })
             .map_err(HistoricalBoardError::Fen)
     }
 
+    /// Construct a new board from a game in PGN.
+    /// The starting position is taken from the `FEN` tag, if present.
+    /// The step pointer is set to the final position of the main line.
+    pub fn from_pgn(
+        pgn: &str,
+        move_tx: Option<Coroutine<BoardAction>>,
+    ) -> Result<Self, HistoricalBoardError> {
+        let pgn = Pgn::from_str(pgn)?;
+
+        let board = match pgn.tag("FEN") {
+            Some(fen) => Board::from_str(fen)?,
+            None => Board::initial(),
+        };
+
+        let Pgn {
+            tags,
+            comment,
+            moves,
+            result,
+        } = pgn;
+
+        let mut root = Node::new(board);
+        root.comment = comment;
+        root.add_pgn_line(&moves, 1)?;
+
+        let mut historical_board = Self {
+            move_tx,
+            root,
+            path: vec![],
+            step_pointer: 0,
+            tags,
+            result,
+            clocks: None,
+            clock_source: ClockSourceHandle::default(),
+            agreement: None,
+            draw_offer: None,
+        };
+
+        historical_board.extend_path();
+        historical_board.step_pointer = historical_board.path.len();
+
+        Ok(historical_board)
+    }
+
+    /// Construct a new board from the `moves` played from the `start` position.
+    /// The moves are not reported, the step pointer is set to the final position.
+    pub(crate) fn from_line(
+        start: Board,
+        moves: &[Move],
+        move_tx: Option<Coroutine<BoardAction>>,
+    ) -> Result<Self, HistoricalBoardError> {
+        let mut historical_board = Self {
+            move_tx: None,
+            root: Node::new(start),
+            path: vec![],
+            step_pointer: 0,
+            tags: vec![],
+            result: GameStatus::Running,
+            clocks: None,
+            clock_source: ClockSourceHandle::default(),
+            agreement: None,
+            draw_offer: None,
+        };
+
+        for &m in moves {
+            historical_board.push_move(m)?;
+        }
+        historical_board.move_tx = move_tx;
+
+        Ok(historical_board)
+    }
+
+    /// Construct a new board from the complete game exported by [`HistoricalBoard::to_state`].
+    /// The state is validated, since it may come from an untrusted source, e.g., a saved session.
+    #[cfg(feature = "serde")]
+    pub(crate) fn from_state(
+        state: &BoardState,
+        move_tx: Option<Coroutine<BoardAction>>,
+    ) -> Result<Self, HistoricalBoardError> {
+        let mut root = Node::new(Board::from_str(&state.fen)?);
+        root.comment = state.comment.clone();
+        root.add_move_states(&state.continuations)?;
+
+        let mut historical_board = Self {
+            move_tx,
+            root,
+            path: state.path.clone(),
+            step_pointer: state.step_pointer,
+            tags: state.tags.clone(),
+            result: state.result,
+            clocks: None,
+            clock_source: ClockSourceHandle::default(),
+            agreement: state
+                .agreement
+                .as_ref()
+                .map(|agreement| (agreement.outcome, agreement.reason)),
+            draw_offer: state.draw_offer,
+        };
+
+        // Every index of the path must select an existing continuation.
+        let mut node = &historical_board.root;
+        for &index in &historical_board.path {
+            node = match node.continuations.get(index) {
+                Some((_, next)) => next,
+                None => return Err(HistoricalBoardError::State("variation path is invalid")),
+            };
+        }
+
+        // A path stopping short of the end of its variation, e.g., of a stale state, is extended.
+        historical_board.extend_path();
+
+        if historical_board.step_pointer > historical_board.path.len() {
+            return Err(HistoricalBoardError::State(
+                "step pointer exceeds the variation path",
+            ));
+        }
+
+        Ok(historical_board)
+    }
+
+    /// Exports the complete game together with the `shapes` drawn over the board,
+    /// which can be restored with [`HistoricalBoard::from_state`].
+    #[cfg(feature = "serde")]
+    pub(crate) fn to_state(&self, shapes: &[Shape]) -> BoardState {
+        BoardState {
+            fen: self.root.board.as_fen(),
+            comment: self.root.comment.clone(),
+            continuations: self.root.to_move_states(),
+            path: self.path.clone(),
+            step_pointer: self.step_pointer,
+            tags: self.tags.clone(),
+            result: self.result,
+            agreement: self
+                .agreement
+                .map(|(outcome, reason)| Agreement { outcome, reason }),
+            draw_offer: self.draw_offer,
+            shapes: shapes.to_vec(),
+        }
+    }
+
     /// Tries to apply a [`Move`] to the [`Board`], which is currently pointed to by the step pointer.
-    /// [`Step`]'s after the step pointer are discarded and the injected moved with the new [`Board`] become the last [`Step`].
+    /// If the move has already been played in this position, its continuation becomes active,
+    /// otherwise the move starts a new variation.
+    /// No moves are discarded.
+    /// If the game is played with clocks, they are switched,
+    /// and the remaining time of the side which has moved is stored with the move.
+    /// A move cannot be made once a flag has fallen.
     pub fn make_move(&mut self, m: Move) -> Result<(), HistoricalBoardError> {
         debug!("Making a move {m:?}");
 
-        // 1 is added because the argument represents the length if the vector after truncation.
-        self.history.truncate(self.step_pointer + 1);
+        self.check_flag();
+        if self.flagged().is_some() {
+            return Err(HistoricalBoardError::Timeout);
+        }
+
+        let previous_path = self.path.clone();
+        let previous_game_over = self.game_over();
+        let side = self.side();
+
+        self.push_move(m)?;
+
+        // Moving declines the draw offered by the opponent.
+        if self.draw_offer.is_some_and(|offered| offered != side) {
+            self.draw_offer = None;
+        }
 
-        let step = self.history.pop().expect(Self::INVARIANT_AT_LEAST_1_STEP);
+        let now = self.clock_source.now();
+        if let Some(remaining) = self
+            .clocks
+            .as_mut()
+            .and_then(|clocks| clocks.switch(side, now))
+        {
+            self.node_mut(self.step_pointer).clock = Some(remaining);
+        }
 
-        let board = step.into_board();
+        self.report_move();
 
-        let new_board = board.make_move(m)?;
+        // Playing on at the end of the active variation does not change it.
+        if !self.path.starts_with(&previous_path) {
+            self.report_variation();
+        }
 
-        self.history
-            .push(Step::Intermediate(IntermediateStep { board, m }));
+        // The end of the game is only reported once, e.g., not for moves played after a resignation.
+        let game_over = self.game_over();
+        if let Some((outcome, reason)) = game_over.filter(|_| game_over != previous_game_over) {
+            if let Some(clocks) = self.clocks.as_mut() {
+                clocks.stop(now);
+            }
 
-        self.history.push(Step::Last(new_board));
+            self.end_game(outcome, reason);
+        }
 
-        self.step_pointer = self.history.len() - 1;
+        Ok(())
+    }
 
-        self.report_move();
+    /// Brings the game to the position reached by the `moves` played from the `start` position.
+    /// Moves following the current position are made as usual, i.e., they are timed and reported.
+    /// Returns the number of moves made, or `None` if the game cannot reach that position,
+    /// because it starts elsewhere or the moves leading to the current position differ.
+    pub(crate) fn catch_up(
+        &mut self,
+        start: &Board,
+        moves: &[Move],
+    ) -> Result<Option<usize>, HistoricalBoardError> {
+        let is_reachable = self.root.board == *start
+            && moves.len() >= self.step_pointer
+            && (0..self.step_pointer)
+                .filter_map(|depth| self.step(depth))
+                .map(|(_, m)| m)
+                .eq(moves[..self.step_pointer].iter().copied());
+
+        if !is_reachable {
+            return Ok(None);
+        }
+
+        let missing = &moves[self.step_pointer..];
+        for &m in missing {
+            self.make_move(m)?;
+        }
+
+        Ok(Some(missing.len()))
+    }
+
+    /// Starting position of the game.
+    #[cfg(feature = "sync")]
+    pub(crate) fn starting_board(&self) -> &Board {
+        &self.root.board
+    }
+
+    /// Moves of the active variation path, including the ones following the current position.
+    #[cfg(feature = "sync")]
+    pub(crate) fn line(&self) -> Vec<Move> {
+        (0..self.path.len())
+            .filter_map(|depth| self.step(depth))
+            .map(|(_, m)| m)
+            .collect()
+    }
+
+    /// Sets up clocks with the given time control, or removes them if there is none.
+    /// The clock of the side to move starts immediately.
+    pub fn set_clocks(&mut self, time_control: Option<TimeControl>, source: ClockSourceHandle) {
+        let side = self.current_board().side();
+
+        self.clocks =
+            time_control.map(|time_control| Clocks::new(time_control, side, source.now()));
+        self.clock_source = source;
+    }
+
+    /// Time control of the game, if it is played with clocks.
+    pub fn time_control(&self) -> Option<TimeControl> {
+        self.clocks.as_ref().map(Clocks::time_control)
+    }
+
+    pub(crate) fn clock_source(&self) -> ClockSourceHandle {
+        self.clock_source.clone()
+    }
+
+    /// Remaining time of the `side`, if the game is played with clocks.
+    pub fn remaining_time(&self, side: Color) -> Option<Duration> {
+        let now = self.clock_source.now();
+
+        self.clocks
+            .as_ref()
+            .map(|clocks| clocks.remaining(side, now))
+    }
+
+    /// Side whose clock is running.
+    pub fn running_clock(&self) -> Option<Color> {
+        self.clocks.as_ref().and_then(Clocks::running)
+    }
+
+    /// Side which has run out of time.
+    pub fn flagged(&self) -> Option<Color> {
+        self.clocks.as_ref().and_then(Clocks::flagged)
+    }
+
+    /// Has the running side run out of time, which is not reported yet?
+    pub fn is_flag_fallen(&self) -> bool {
+        let now = self.clock_source.now();
+
+        self.clocks.as_ref().is_some_and(|clocks| {
+            clocks
+                .running()
+                .is_some_and(|side| clocks.remaining(side, now).is_zero())
+        })
+    }
+
+    /// Checks whether the running side has run out of time.
+    /// A fallen flag ends the game and is reported as [`BoardAction::GameOver`].
+    /// Returns the side which has newly run out of time.
+    pub fn check_flag(&mut self) -> Option<Color> {
+        let now = self.clock_source.now();
+        let side = self.clocks.as_mut()?.check_flag(now)?;
+
+        debug!("{} has run out of time", side.as_long_str());
+        self.end_game(win(side.inv()), Termination::Timeout);
+
+        Some(side)
+    }
+
+    /// The `side` resigns, which ends the game unless it is already over.
+    pub fn resign(&mut self, side: Color) {
+        debug!("{} resigns", side.as_long_str());
+        self.agree(win(side.inv()), Termination::Resignation);
+    }
+
+    /// The `side` offers a draw, which is reported as [`BoardAction::DrawOffered`].
+    /// The offer stands until the opponent accepts it or moves.
+    pub fn offer_draw(&mut self, side: Color) {
+        if self.game_over().is_some() {
+            return;
+        }
+
+        debug!("{} offers a draw", side.as_long_str());
+        self.draw_offer = Some(side);
+
+        if let Some(tx) = self.move_tx.as_ref() {
+            tx.send(BoardAction::DrawOffered(side));
+        }
+    }
+
+    /// Side which has offered a draw, if the offer stands.
+    #[cfg(feature = "sync")]
+    pub fn draw_offer(&self) -> Option<Color> {
+        self.draw_offer
+    }
+
+    /// Accepts the draw offer, which ends the game.
+    pub fn accept_draw(&mut self) -> Result<(), HistoricalBoardError> {
+        if self.draw_offer.is_none() {
+            return Err(HistoricalBoardError::NoDrawOffer);
+        }
+
+        debug!("Draw offer is accepted");
+        self.agree(GameStatus::Draw, Termination::DrawAgreement);
+
+        Ok(())
+    }
+
+    /// Result of the game ended by the players rather than on the board, if any.
+    #[cfg(feature = "sync")]
+    pub(crate) fn agreement(&self) -> Option<(GameStatus, Termination)> {
+        self.agreement
+    }
+
+    /// Ends the game by the agreement of the players, unless it is already over.
+    fn agree(&mut self, outcome: GameStatus, reason: Termination) {
+        if self.game_over().is_some() {
+            return;
+        }
+
+        if let Some(clocks) = self.clocks.as_mut() {
+            clocks.stop(self.clock_source.now());
+        }
+
+        self.agreement = Some((outcome, reason));
+        self.draw_offer = None;
+        self.end_game(outcome, reason);
+    }
+
+    /// Records the end of the game and reports it.
+    fn end_game(&mut self, outcome: GameStatus, reason: Termination) {
+        // Only the main line determines the result of the game.
+        if self.path[..self.step_pointer].iter().all(|&i| i == 0) {
+            self.result = outcome;
+        }
+
+        self.report_game_over(outcome, reason);
+    }
+
+    /// Checks whether the position currently pointed to by the step pointer ends the game.
+    /// Returns the result of the game and the reason it has ended.
+    /// A fallen flag, a resignation or an agreed draw end the game in any position.
+    pub fn game_over(&self) -> Option<(GameStatus, Termination)> {
+        if let Some(side) = self.flagged() {
+            return Some((win(side.inv()), Termination::Timeout));
+        }
+
+        if self.agreement.is_some() {
+            return self.agreement;
+        }
+
+        let board = self.current_board_view();
+
+        // Checkmate, stalemate and insufficient material take precedence over the other draws.
+        let outcome = board.calc_outcome();
+        if let Some(outcome) = outcome.filter(|o| o.passes(OutcomeFilter::Strict)) {
+            return Termination::from_outcome(outcome)
+                .map(|reason| (GameStatus::from(Some(outcome)), reason));
+        }
+
+        if self.repetitions() >= 3 {
+            return Some((GameStatus::Draw, Termination::ThreefoldRepetition));
+        }
+
+        outcome
+            .and_then(Termination::from_outcome)
+            .map(|reason| (GameStatus::from(outcome), reason))
+    }
+
+    /// Counts occurrences of the position currently pointed to by the step pointer
+    /// along the active variation path.
+    /// Only positions since the last capture or pawn move are considered, as earlier ones cannot repeat.
+    fn repetitions(&self) -> usize {
+        let board = self.current_board_view();
+        let reversible = usize::from(board.raw().move_counter).min(self.step_pointer);
+
+        let mut node = &self.root;
+        let mut hashes = vec![node.board.zobrist_hash()];
+        for &index in &self.path[..self.step_pointer] {
+            node = &node.continuations[index].1;
+            hashes.push(node.board.zobrist_hash());
+        }
+
+        let hash = board.zobrist_hash();
+        hashes[self.step_pointer - reversible..]
+            .iter()
+            .filter(|&&h| h == hash)
+            .count()
+    }
+
+    /// Applies a [`Move`] as [`Self::make_move`] does, but does not report it.
+    fn push_move(&mut self, m: Move) -> Result<(), HistoricalBoardError> {
+        let depth = self.step_pointer;
+
+        let index = self.node_mut(depth).continue_with(m)?;
+
+        self.path.truncate(depth);
+        self.path.push(index);
+        self.extend_path();
+
+        self.step_pointer = depth + 1;
 
         Ok(())
     }
 
     /// Insights to the history.
     ///
-    /// Returns the [`Move`] associated with the [`Step`] that immediately precedes
-    /// the [`Step`] currently pointed to by the step pointer.
+    /// Returns the [`Move`] which leads to the position currently pointed to by the step pointer.
     pub fn get_previous_move(&self) -> Option<Move> {
         debug!(
             "Get previous move: pointer = {}/{}",
             self.step_pointer,
-            self.history.len() - 1
+            self.path.len()
         );
 
-        if self.step_pointer == 0 {
-            return None;
-        }
-
-        self.history
-            .get(self.step_pointer.saturating_sub(1))
-            .and_then(|s| s.as_move())
+        self.step_pointer
+            .checked_sub(1)
+            .and_then(|depth| self.step(depth))
+            .map(|(_, m)| m)
             .inspect(|m| debug!("Previous move: {m:?}"))
     }
 
     /// Insights to the history.
     ///
-    /// Returns the [`Move`] associated with the [`Step`] that immediately follows
-    /// the [`Step`] currently pointed to by the step pointer.
+    /// Returns the SAN-encoded [`Move`] which leads to the position currently pointed to by the step pointer.
+    pub fn previous_san(&self) -> Option<String> {
+        self.step_pointer
+            .checked_sub(1)
+            .and_then(|depth| self.step(depth))
+            .map(|(board, m)| san(board, m))
+    }
+
+    /// Insights to the history.
+    ///
+    /// Returns the [`Move`] played in the position currently pointed to by the step pointer
+    /// along the active variation path.
     pub fn get_next_move(&self) -> Option<Move> {
         debug!(
             "Get next move: pointer = {}/{}",
             self.step_pointer,
-            self.history.len() - 1
+            self.path.len()
         );
 
-        if self.step_pointer == self.history.len() - 1 {
-            return None;
-        }
-
-        self.history
-            .get(self.step_pointer)
-            .and_then(|s| s.as_move())
+        self.step(self.step_pointer)
+            .map(|(_, m)| m)
             .inspect(|m| debug!("Next move: {m:?}"))
     }
 
@@ -111,90 +548,205 @@ impl HistoricalBoard {
     /// Decrements the step pointer.
     pub fn step_back(&mut self) {
         if self.step_pointer == 0 {
-            debug!("Stepping back in impossible. Current step is the last.");
+            debug!("Stepping back in impossible. Current step is the first.");
             return;
         }
 
         // Subtraction is safe.
         self.step_pointer -= 1;
 
-        let current_step_view = self
-            .history
-            .get(self.step_pointer)
-            .expect("Step pointer out of bounds");
-
         if let Some(tx) = self.move_tx.as_ref() {
-            let Step::Intermediate(s) = current_step_view else {
-                panic!("Stepping back from any Step should be an intermediate step.");
-            };
-
-            tx.send(BoardAction::StepBack(s.into()));
+            let (board, m) = self
+                .step(self.step_pointer)
+                .expect("Step pointer is within the active variation path");
+
+            tx.send(BoardAction::StepBack(SanMove {
+                clock: self.node(self.step_pointer + 1).clock,
+                ..SanMove::from_move(board, m)
+            }));
         }
 
         debug!(
             "Stepping back: new pointer = {}/{}",
             self.step_pointer,
-            self.history.len() - 1
+            self.path.len()
         );
     }
 
     /// Navigation through the history.
     ///
     /// Increments the step pointer,
-    /// provided the step pointer is not pointing to the last [`Step`].
+    /// provided the step pointer is not pointing to the last position of the active variation path.
     pub fn step_forward(&mut self) {
-        let current_step_view = self
-            .history
-            .get(self.step_pointer)
-            .expect("Step pointer out of bounds");
-
-        // if self.step_pointer < self.history.len() - 1 {
-        //     self.step_pointer += 1;
-        // }
-
-        // We can move forward from an intermediate step.
-        let Step::Intermediate(s) = current_step_view else {
+        let Some((board, m)) = self.step(self.step_pointer) else {
             debug!("Stepping forward in impossible. Current step is the last.");
             return;
         };
 
+        let san_move = SanMove::from_move(board, m);
+
         self.step_pointer += 1;
 
+        let san_move = SanMove {
+            clock: self.node(self.step_pointer).clock,
+            ..san_move
+        };
+
         if let Some(tx) = self.move_tx.as_ref() {
-            tx.send(BoardAction::StepForward(s.into()));
+            tx.send(BoardAction::StepForward(san_move));
         }
 
         debug!(
             "Stepping forward: new pointer = {}/{}",
             self.step_pointer,
-            self.history.len() - 1
+            self.path.len()
         );
     }
 
+    /// Returns the last [`Move`] of the active variation path.
     pub fn last_move(&self) -> Option<Move> {
-        if let [.., Step::Intermediate(IntermediateStep { m, .. }), _last] = &self.history[..] {
-            Some(*m)
-        } else {
-            None
-        }
+        self.path
+            .len()
+            .checked_sub(1)
+            .and_then(|depth| self.step(depth))
+            .map(|(_, m)| m)
+    }
+
+    /// Is the step pointer at the end of the active variation path?
+    pub(crate) fn is_at_end(&self) -> bool {
+        self.step_pointer == self.path.len()
     }
 
+    /// Removes the last [`Move`] of the active variation path and
+    /// sets the step pointer to the position preceding it.
+    /// If the move has started a variation, the active variation path follows the main line
+    /// of that position again.
+    /// If the game is played with clocks, the side which has played the move gets its time back.
     pub fn revert_last_move(&mut self) -> Option<Move> {
         debug!("Reverting the last move");
 
-        let last = self.history.pop().expect(Self::INVARIANT_AT_LEAST_1_STEP);
-        let last_but_1 = self.history.pop();
+        let depth = self.path.len().checked_sub(1)?;
+        let index = self.path.pop()?;
+
+        let (m, node) = self.node_mut(depth).continuations.remove(index);
 
-        let (board, m) = match last_but_1 {
-            None => (last.into_board(), None),
-            Some(Step::Intermediate(IntermediateStep { board, m })) => (board, Some(m)),
-            Some(Step::Last(_)) => unreachable!("{}", Self::INVARIANT_LAST_BUT_1_IS_INTERMEDIATE),
+        self.step_pointer = depth;
+        self.extend_path();
+
+        // Other moves played in the position now continue the active variation.
+        if self.path.len() > depth {
+            self.report_variation();
+        }
+
+        // The side which has played the reverted move is to move again.
+        let side = self.node(depth).board.side();
+        let now = self.clock_source.now();
+        if let (Some(clocks), Some(remaining)) = (self.clocks.as_mut(), node.clock) {
+            clocks.take_back(side, remaining, now);
+        }
+
+        Some(m)
+    }
+
+    /// Switches to the next (or the previous) variation branching off with the move
+    /// that leads to the current position.
+    pub fn switch_variation(&mut self, forward: bool) {
+        let Some(depth) = self.step_pointer.checked_sub(1) else {
+            debug!("Switching variations is impossible in the starting position");
+            return;
         };
 
-        self.history.push(Step::Last(board));
-        self.step_pointer = self.history.len() - 1;
+        let count = self.node(depth).continuations.len();
+        if count < 2 {
+            debug!("Switching variations is impossible: no alternative moves");
+            return;
+        }
+
+        let index = self.path[depth];
+        let index = if forward {
+            (index + 1) % count
+        } else {
+            (index + count - 1) % count
+        };
+
+        self.path.truncate(depth);
+        self.path.push(index);
+        self.extend_path();
+
+        self.report_variation();
+    }
+
+    /// Makes the variation containing the current position the main line
+    /// at the position where it branches off.
+    pub fn promote_variation(&mut self) {
+        let Some(depth) = self.branch_depth() else {
+            debug!("Current position is on the main line");
+            return;
+        };
+
+        let index = self.path[depth];
+        let continuations = &mut self.node_mut(depth).continuations;
+        let continuation = continuations.remove(index);
+        continuations.insert(0, continuation);
+
+        self.path[depth] = 0;
+
+        self.report_variation();
+    }
+
+    /// Deletes the variation containing the current position, starting from the position
+    /// where it branches off. The step pointer is set to that position.
+    pub fn delete_variation(&mut self) {
+        let Some(depth) = self.branch_depth() else {
+            debug!("Current position does not belong to any variation");
+            return;
+        };
+
+        let index = self.path[depth];
+        self.node_mut(depth).continuations.remove(index);
+
+        self.path.truncate(depth);
+        self.extend_path();
+        self.step_pointer = depth;
+
+        self.report_variation();
+    }
+
+    /// Export the complete game history in PGN.
+    pub fn to_pgn(&self) -> String {
+        write_pgn(
+            &self.tags,
+            &self.root.board,
+            self.root.comment.as_deref(),
+            &self.root.to_pgn_line(),
+            self.result,
+        )
+    }
+
+    /// UCI `position` command describing the position currently pointed to by the step pointer:
+    /// the starting position in FEN notation and the moves leading from it to the current one.
+    pub fn uci_position(&self) -> String {
+        let mut command = format!("position fen {}", self.root.board.as_fen());
+
+        if self.step_pointer > 0 {
+            command.push_str(" moves");
+
+            for (_, m) in (0..self.step_pointer).filter_map(|depth| self.step(depth)) {
+                command.push_str(&format!(" {m}"));
+            }
+        }
+
+        command
+    }
 
-        m
+    /// PGN tag pairs describing the game.
+    pub fn tags(&self) -> &[(String, String)] {
+        &self.tags
+    }
+
+    /// Game termination marker.
+    pub fn result(&self) -> GameStatus {
+        self.result
     }
 
     pub fn side_to_move(&self) -> Color {
@@ -210,7 +762,7 @@ impl HistoricalBoard {
     }
 
     pub fn set_end(&mut self) {
-        self.step_pointer = self.history.len() - 1;
+        self.step_pointer = self.path.len();
 
         if let Some(tx) = self.move_tx.as_ref() {
             tx.send(BoardAction::SetEndPosition);
@@ -225,62 +777,90 @@ impl HistoricalBoard {
         )
     }
 
-    /// Retrieve references to:
-    /// - the board immediately preceding the current last board,
-    /// - and the move that transitions the preceding board to the current last board.
-    fn last_intermediate_step(&self) -> Option<(&Board, &Move)> {
-        if let [.., Step::Intermediate(IntermediateStep { board, m }), Step::Last(_)] =
-            &self.history[..]
-        {
-            Some((board, m))
-        } else {
-            None
+    /// Returns the [`Node`] reached after `depth` moves along the active variation path.
+    fn node(&self, depth: usize) -> &Node {
+        self.path[..depth]
+            .iter()
+            .fold(&self.root, |node, &i| &node.continuations[i].1)
+    }
+
+    /// Returns the [`Node`] reached after `depth` moves along the active variation path.
+    fn node_mut(&mut self, depth: usize) -> &mut Node {
+        self.path[..depth]
+            .iter()
+            .fold(&mut self.root, |node, &i| &mut node.continuations[i].1)
+    }
+
+    /// Returns the [`Move`] played after `depth` moves along the active variation path
+    /// together with the [`Board`] it is played on.
+    fn step(&self, depth: usize) -> Option<(&Board, Move)> {
+        let index = *self.path.get(depth)?;
+        let node = self.node(depth);
+
+        Some((&node.board, node.continuations[index].0))
+    }
+
+    /// Returns the depth of the last position before the current one where a variation is selected
+    /// instead of the main line, i.e., the position where the variation containing the current position
+    /// branches off. Positions on the main line do not belong to any variation.
+    fn branch_depth(&self) -> Option<usize> {
+        (0..self.step_pointer).rev().find(|&d| self.path[d] != 0)
+    }
+
+    /// Extends the active variation path by following the main lines to the end.
+    fn extend_path(&mut self) {
+        let mut node = self.node(self.path.len());
+        let mut extension = vec![];
+
+        while let Some((_, next)) = node.continuations.first() {
+            extension.push(0);
+            node = next;
         }
+
+        self.path.extend(extension);
     }
 
+    /// Returns the last [`Board`] of the active variation path.
     fn current_board(&self) -> &Board {
-        if let [.., Step::Last(board)] = &self.history[..] {
-            board
-        } else {
-            panic!("[BUG] History must have the last step");
-        }
+        &self.node(self.path.len()).board
     }
 
     /// Returns the [`Board`] currently referenced by the `step_pointer`.
     fn current_board_view(&self) -> &Board {
-        self.current_step_view().as_board()
-    }
-
-    /// Returns the [`Step`] currently referenced by the `step_pointer`.
-    fn current_step_view(&self) -> &Step {
-        self.history
-            .get(self.step_pointer)
-            .expect("Step pointer out of bounds")
+        &self.node(self.step_pointer).board
     }
 
     fn report_move(&self) {
         if let Some(ref tx) = self.move_tx {
-            let Some((board, m)) = self.last_intermediate_step() else {
+            let Some((board, m)) = self
+                .step_pointer
+                .checked_sub(1)
+                .and_then(|depth| self.step(depth))
+            else {
                 return;
             };
 
             // There is a valid move and a coroutine to report it.
-            let san_repr = m
-                .styled(board, Style::San)
-                .expect("Board and move form a valid intermediate step")
-                .to_string();
+            tx.send(BoardAction::Apply(SanMove {
+                clock: self.node(self.step_pointer).clock,
+                ..SanMove::from_move(board, m)
+            }));
+        }
+    }
 
-            let src_cell = board.get(m.src());
+    fn report_game_over(&self, outcome: GameStatus, reason: Termination) {
+        debug!("Game over: {outcome} ({reason})");
 
-            let piece = src_cell
-                .piece()
-                .expect("Move is valid, thus src must contain a piece");
+        if let Some(ref tx) = self.move_tx {
+            tx.send(BoardAction::GameOver { outcome, reason });
+        }
+    }
 
-            let color = src_cell
-                .color()
-                .expect("Move is valid, thus src must contain a piece");
+    fn report_variation(&self) {
+        debug!("Active variation path: {:?}", self.path);
 
-            tx.send(BoardAction::Apply(SanMove::new(&san_repr, piece, color)));
+        if let Some(ref tx) = self.move_tx {
+            tx.send(BoardAction::Variation(self.path.clone()));
         }
     }
 }
@@ -299,59 +879,156 @@ impl Deref for HistoricalBoard {
     }
 }
 
-enum Step {
-    Last(Board),
-    Intermediate(IntermediateStep),
+/// Position in the game together with all moves played in it.
+struct Node {
+    board: Board,
+    /// Moves played in this position and the positions they lead to.
+    /// The first continuation belongs to the main line, the other ones are variations.
+    continuations: Vec<(Move, Node)>,
+    /// Remaining time of the side which has played the move leading to this position,
+    /// if the game is played with clocks.
+    clock: Option<Duration>,
+    /// Numeric annotation glyphs of the move leading to this position.
+    nags: Vec<u8>,
+    /// Comment of the move leading to this position, or of the game for the starting position.
+    comment: Option<String>,
 }
 
-impl Step {
-    fn into_board(self) -> Board {
-        match self {
-            Step::Last(board) => board,
-            Step::Intermediate(IntermediateStep { board, .. }) => board,
+impl Node {
+    fn new(board: Board) -> Self {
+        Self {
+            board,
+            continuations: vec![],
+            clock: None,
+            nags: vec![],
+            comment: None,
         }
     }
 
-    fn as_move(&self) -> Option<Move> {
-        match self {
-            Step::Last(_) => None,
-            Step::Intermediate(IntermediateStep { m, .. }) => Some(*m),
+    /// Returns the index of the continuation starting with the given [`Move`].
+    /// If the move has not been played in this position yet, a new continuation is added.
+    fn continue_with(&mut self, m: Move) -> Result<usize, HistoricalBoardError> {
+        if let Some(index) = self.continuations.iter().position(|(c, _)| *c == m) {
+            return Ok(index);
         }
+
+        let board = self.board.make_move(m)?;
+        self.continuations.push((m, Node::new(board)));
+
+        Ok(self.continuations.len() - 1)
     }
 
-    fn as_board(&self) -> &Board {
-        match self {
-            Step::Last(board) => board,
-            Step::Intermediate(IntermediateStep { board, .. }) => board,
+    /// Adds a line of PGN moves played from this position. The first move is played at `ply`.
+    fn add_pgn_line(&mut self, moves: &[PgnMove], ply: usize) -> Result<(), HistoricalBoardError> {
+        let Some((first, rest)) = moves.split_first() else {
+            return Ok(());
+        };
+
+        let m = Move::from_san(&first.san, &self.board).map_err(|_| HistoricalBoardError::Pgn {
+            ply,
+            token: first.san.clone(),
+        })?;
+
+        let index = self.continue_with(m)?;
+
+        // A move repeated in several lines keeps the annotations it has been given first.
+        let next = &mut self.continuations[index].1;
+        if next.nags.is_empty() {
+            next.nags.clone_from(&first.nags);
+        }
+        if next.comment.is_none() {
+            next.comment.clone_from(&first.comment);
+        }
+
+        // Variations are alternatives to the first move, i.e., they are played from this position.
+        for variation in &first.variations {
+            self.add_pgn_line(variation, ply)?;
         }
+
+        self.continuations[index].1.add_pgn_line(rest, ply + 1)
     }
-}
 
-struct IntermediateStep {
-    board: Board,
-    m: Move,
-}
+    /// Adds the exported continuations of this position, see [`Node::to_move_states`].
+    #[cfg(feature = "serde")]
+    fn add_move_states(&mut self, states: &[MoveState]) -> Result<(), HistoricalBoardError> {
+        for state in states {
+            let m = Move::from_uci_legal(&state.uci, &self.board)?;
+            let index = self.continue_with(m)?;
+
+            let next = &mut self.continuations[index].1;
+            next.clock = state.clock;
+            next.nags = state.nags.clone();
+            next.comment = state.comment.clone();
+            next.add_move_states(&state.continuations)?;
+        }
 
-impl From<&IntermediateStep> for SanMove {
-    fn from(step: &IntermediateStep) -> Self {
-        let IntermediateStep { board, m } = step;
+        Ok(())
+    }
 
-        let san_repr = m
-            .styled(board, Style::San)
-            .expect("Board and move form a valid intermediate step")
-            .to_string();
+    /// Exports the continuations of this position, including all variations.
+    #[cfg(feature = "serde")]
+    fn to_move_states(&self) -> Vec<MoveState> {
+        self.continuations
+            .iter()
+            .map(|(m, next)| MoveState {
+                uci: m.to_string(),
+                clock: next.clock,
+                nags: next.nags.clone(),
+                comment: next.comment.clone(),
+                continuations: next.to_move_states(),
+            })
+            .collect()
+    }
 
-        let src_cell = board.get(m.src());
+    /// Converts the main line played from this position, including all variations, to PGN moves.
+    fn to_pgn_line(&self) -> Vec<PgnMove> {
+        let mut line = vec![];
+        let mut node = self;
+
+        while let Some(((m, next), alternatives)) = node.continuations.split_first() {
+            let variations = alternatives
+                .iter()
+                .map(|(m, alternative)| {
+                    let mut variation = vec![alternative.to_pgn_move(&node.board, *m)];
+                    variation.extend(alternative.to_pgn_line());
+                    variation
+                })
+                .collect();
+
+            line.push(PgnMove {
+                variations,
+                ..next.to_pgn_move(&node.board, *m)
+            });
+
+            node = next;
+        }
 
-        let piece = src_cell
-            .piece()
-            .expect("Move is valid, thus src must contain a piece");
+        line
+    }
 
-        let color = src_cell
-            .color()
-            .expect("Move is valid, thus src must contain a piece");
+    /// Converts the move `m` played on the `board` and leading to this position to a PGN move
+    /// without variations.
+    fn to_pgn_move(&self, board: &Board, m: Move) -> PgnMove {
+        PgnMove {
+            san: san(board, m),
+            nags: self.nags.clone(),
+            comment: self.comment.clone(),
+            variations: vec![],
+        }
+    }
+}
 
-        SanMove::new(&san_repr, piece, color)
+fn san(board: &Board, m: Move) -> String {
+    m.styled(board, Style::San)
+        .expect("Board and move form a valid step")
+        .to_string()
+}
+
+/// Result of the game won by the `side`.
+fn win(side: Color) -> GameStatus {
+    match side {
+        Color::White => GameStatus::White,
+        Color::Black => GameStatus::Black,
     }
 }
 
@@ -361,4 +1038,293 @@ pub enum HistoricalBoardError {
     Fen(#[from] FenParseError),
     #[error("Move validation error: {0}")]
     Validation(#[from] ValidateError),
+    #[error("Position validation error: {0}")]
+    Position(#[from] board::ValidateError),
+    #[error("SAN parsing error: {0}")]
+    San(#[from] san::ParseError),
+    #[error("Move is made after the flag has fallen")]
+    Timeout,
+    #[error("No draw is offered")]
+    NoDrawOffer,
+    #[error("No move waits for a confirmation")]
+    NoPendingMove,
+    #[error("Move waits for a confirmation")]
+    MovePending,
+    #[error("Puzzle solution is empty")]
+    EmptySolution,
+    #[error("Position cannot be changed while a puzzle is being solved")]
+    PuzzleInProgress,
+    #[error("UCI parsing error: {0}")]
+    Uci(#[from] uci::ParseError),
+    #[error("Board state is inconsistent: {0}")]
+    State(&'static str),
+    #[error("PGN parsing error at ply {ply}: unexpected token `{token}`")]
+    Pgn { ply: usize, token: String },
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::clock::ManualClock;
+
+    fn board(pgn: &str) -> HistoricalBoard {
+        HistoricalBoard::from_pgn(pgn, None).expect("PGN is valid")
+    }
+
+    fn play(board: &mut HistoricalBoard, san: &str) {
+        let m = Move::from_san(san, board).expect("Move is legal");
+        board.make_move(m).expect("Move can be made");
+    }
+
+    fn movetext(board: &HistoricalBoard) -> String {
+        board
+            .to_pgn()
+            .split("\n\n")
+            .nth(1)
+            .expect("Movetext follows the tags")
+            .trim()
+            .to_string()
+    }
+
+    #[test]
+    fn move_played_again_reuses_its_continuation() {
+        let mut board = board("1. e4 e5 2. Nf3 *");
+        board.set_start();
+
+        play(&mut board, "e4");
+        assert_eq!(board.root.continuations.len(), 1);
+        assert_eq!((board.path.clone(), board.step_pointer), (vec![0, 0, 0], 1));
+
+        play(&mut board, "e5");
+        play(&mut board, "Nf3");
+        assert_eq!(movetext(&board), "1. e4 e5 2. Nf3 *");
+    }
+
+    #[test]
+    fn new_move_starts_a_variation() {
+        let mut board = board("1. e4 e5 2. Nf3 *");
+        board.step_back();
+        board.step_back();
+
+        play(&mut board, "c5");
+        assert_eq!((board.path.clone(), board.step_pointer), (vec![0, 1], 2));
+        assert_eq!(movetext(&board), "1. e4 e5 (1... c5) 2. Nf3 *");
+
+        play(&mut board, "Nf3");
+        assert_eq!(board.path, vec![0, 1, 0]);
+        assert_eq!(movetext(&board), "1. e4 e5 (1... c5 2. Nf3) 2. Nf3 *");
+    }
+
+    #[test]
+    fn switching_cycles_through_sibling_variations() {
+        let mut board = board("1. e4 (1. d4) (1. c4) e5 *");
+        board.set_start();
+        board.step_forward();
+
+        board.switch_variation(true);
+        assert_eq!(board.path, vec![1]);
+        assert_eq!(board.previous_san().as_deref(), Some("d4"));
+
+        board.switch_variation(true);
+        assert_eq!(board.path, vec![2]);
+
+        board.switch_variation(true);
+        assert_eq!(board.path, vec![0, 0]);
+        assert_eq!(board.step_pointer, 1);
+
+        board.switch_variation(false);
+        assert_eq!(board.path, vec![2]);
+        assert_eq!(board.previous_san().as_deref(), Some("c4"));
+    }
+
+    #[test]
+    fn promoted_variation_becomes_the_main_line() {
+        let mut board = board("1. e4 e5 (1... c5 2. Nf3 (2. c3) d6) 2. Nf3 *");
+        board.set_start();
+        board.step_forward();
+        board.step_forward();
+        board.switch_variation(true);
+        board.step_forward();
+        board.switch_variation(true);
+        assert_eq!((board.path.clone(), board.step_pointer), (vec![0, 1, 1], 3));
+
+        board.promote_variation();
+        assert_eq!(board.path, vec![0, 1, 0]);
+        assert_eq!(
+            movetext(&board),
+            "1. e4 e5 (1... c5 2. c3 (2. Nf3 d6)) 2. Nf3 *"
+        );
+
+        board.promote_variation();
+        assert_eq!(board.path, vec![0, 0, 0]);
+        assert_eq!(
+            movetext(&board),
+            "1. e4 c5 (1... e5 2. Nf3) 2. c3 (2. Nf3 d6) *"
+        );
+
+        // The main line cannot be promoted any further.
+        board.promote_variation();
+        assert_eq!(board.path, vec![0, 0, 0]);
+    }
+
+    #[test]
+    fn deleted_variation_is_removed_from_its_branch_point() {
+        let mut board = board("1. e4 e5 (1... c5 2. Nf3 (2. c3) d6) 2. Nf3 *");
+        board.set_start();
+        board.step_forward();
+        board.step_forward();
+        board.switch_variation(true);
+        board.step_forward();
+        board.switch_variation(true);
+
+        board.delete_variation();
+        assert_eq!(
+            (board.path.clone(), board.step_pointer),
+            (vec![0, 1, 0, 0], 2)
+        );
+        assert_eq!(movetext(&board), "1. e4 e5 (1... c5 2. Nf3 d6) 2. Nf3 *");
+
+        board.step_forward();
+        board.step_forward();
+        board.delete_variation();
+        assert_eq!((board.path.clone(), board.step_pointer), (vec![0, 0, 0], 1));
+        assert_eq!(movetext(&board), "1. e4 e5 2. Nf3 *");
+    }
+
+    #[test]
+    fn main_line_below_a_branch_point_is_not_deleted() {
+        let mut board = board("1. e4 e5 (1... c5) 2. Nf3 *");
+        assert_eq!((board.path.clone(), board.step_pointer), (vec![0, 0, 0], 3));
+
+        board.delete_variation();
+        assert_eq!((board.path.clone(), board.step_pointer), (vec![0, 0, 0], 3));
+        assert_eq!(movetext(&board), "1. e4 e5 (1... c5) 2. Nf3 *");
+    }
+
+    #[test]
+    fn clock_of_the_side_to_move_runs_after_a_move_from_a_previous_position() {
+        let source = ManualClock::new();
+        let mut board = board("*");
+        board.set_clocks(
+            Some(TimeControl::sudden_death(Duration::from_secs(60))),
+            source.clone().into(),
+        );
+
+        play(&mut board, "e4");
+        play(&mut board, "e5");
+        board.step_back();
+
+        source.advance(Duration::from_secs(10));
+        play(&mut board, "c5");
+        assert_eq!(board.running_clock(), Some(Color::White));
+        assert_eq!(
+            board.remaining_time(Color::Black),
+            Some(Duration::from_secs(60))
+        );
+        assert_eq!(
+            board.remaining_time(Color::White),
+            Some(Duration::from_secs(50))
+        );
+    }
+
+    #[test]
+    fn reverted_move_gives_the_time_back() {
+        let source = ManualClock::new();
+        let mut board = board("*");
+        board.set_clocks(
+            Some(TimeControl::sudden_death(Duration::from_secs(60))),
+            source.clone().into(),
+        );
+
+        source.advance(Duration::from_secs(10));
+        play(&mut board, "e4");
+        source.advance(Duration::from_secs(5));
+        play(&mut board, "e5");
+
+        source.advance(Duration::from_secs(3));
+        board.revert_last_move();
+        assert_eq!(board.running_clock(), Some(Color::Black));
+        assert_eq!(
+            board.remaining_time(Color::Black),
+            Some(Duration::from_secs(55))
+        );
+        assert_eq!(
+            board.remaining_time(Color::White),
+            Some(Duration::from_secs(47))
+        );
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn state_survives_a_json_round_trip() {
+        use crate::ShapeColor;
+        use owlchess::{Coord, File, Rank};
+
+        let mut board = board("{Opening} 1. e4! {Good} e5 (1... c5 $2 2. Nf3 d6) 2. Nf3 *");
+        board.step_back();
+        board.switch_variation(true);
+        board.step_forward();
+
+        let shapes = [
+            Shape::Arrow {
+                src: Coord::from_parts(File::E, Rank::R2),
+                dst: Coord::from_parts(File::E, Rank::R4),
+                color: ShapeColor::Green,
+            },
+            Shape::Circle {
+                square: Coord::from_parts(File::D, Rank::R5),
+                color: ShapeColor::Red,
+            },
+        ];
+
+        let state = board.to_state(&shapes);
+        let json = serde_json::to_string(&state).expect("State is serializable");
+        let parsed: BoardState = serde_json::from_str(&json).expect("State is deserializable");
+        assert_eq!(parsed, state);
+
+        let restored = HistoricalBoard::from_state(&parsed, None).expect("State is valid");
+        assert_eq!(restored.to_state(parsed.shapes()), state);
+        assert_eq!(
+            (restored.path.clone(), restored.step_pointer),
+            (board.path.clone(), board.step_pointer)
+        );
+        assert_eq!(restored.to_pgn(), board.to_pgn());
+    }
+
+    #[test]
+    fn reverting_a_variation_move_returns_to_the_main_line() {
+        let mut board = board("1. e4 e5 (1... c5) 2. Nf3 *");
+        board.step_back();
+        board.switch_variation(true);
+        assert_eq!((board.path.clone(), board.step_pointer), (vec![0, 1], 2));
+
+        let reverted = board.revert_last_move().map(|m| m.to_string());
+        assert_eq!(reverted.as_deref(), Some("c7c5"));
+        assert_eq!((board.path.clone(), board.step_pointer), (vec![0, 0, 0], 1));
+        assert_eq!(
+            board.last_move().map(|m| m.to_string()).as_deref(),
+            Some("g1f3")
+        );
+        assert_eq!(movetext(&board), "1. e4 e5 2. Nf3 *");
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn truncated_path_of_a_state_is_extended() {
+        let board = board("1. e4 e5 (1... c5 2. Nf3 d6) 2. Nf3 *");
+        let mut state = board.to_state(&[]);
+        state.path = vec![0, 1];
+        state.step_pointer = 1;
+
+        let restored = HistoricalBoard::from_state(&state, None).expect("State is valid");
+        assert_eq!(
+            (restored.path.clone(), restored.step_pointer),
+            (vec![0, 1, 0, 0], 1)
+        );
+        assert!(!restored.is_at_end());
+        assert_eq!(
+            restored.last_move().map(|m| m.to_string()).as_deref(),
+            Some("d7d6")
+        );
+    }
 }
@@ -1,5 +1,12 @@
 mod action;
 mod board;
+mod pgn;
+#[cfg(feature = "serde")]
+mod state;
+mod termination;
 
 pub use action::BoardAction;
-pub use board::HistoricalBoard;
+pub use board::{HistoricalBoard, HistoricalBoardError};
+#[cfg(feature = "serde")]
+pub use state::BoardState;
+pub use termination::Termination;
@@ -0,0 +1,769 @@
+use crate::history::board::HistoricalBoardError;
+use owlchess::{Board, Color, GameStatus};
+use std::fmt::Write;
+use std::iter::Peekable;
+use std::str::{CharIndices, FromStr};
+use tracing::warn;
+
+/// Game described in Portable Game Notation (PGN).
+#[derive(Debug, PartialEq)]
+pub(crate) struct Pgn {
+    /// Tag pairs in the order of their appearance.
+    pub tags: Vec<(String, String)>,
+    /// Comment preceding the first move of the game.
+    pub comment: Option<String>,
+    /// Main line of the game.
+    pub moves: Vec<PgnMove>,
+    /// Game termination marker.
+    pub result: GameStatus,
+}
+
+impl Pgn {
+    /// Returns the value of the tag with the given name, if present.
+    pub fn tag(&self, name: &str) -> Option<&str> {
+        self.tags
+            .iter()
+            .find_map(|(n, v)| if n == name { Some(v.as_str()) } else { None })
+    }
+}
+
+/// SAN-encoded move together with its annotations and the variations which can be played instead of it.
+#[derive(Debug, Default, PartialEq)]
+pub(crate) struct PgnMove {
+    pub san: String,
+    /// Numeric annotation glyphs, e.g., 1 for `$1` or `!`.
+    pub nags: Vec<u8>,
+    /// Comment following the move.
+    pub comment: Option<String>,
+    /// Recursive annotation variations, i.e., alternatives to this move.
+    pub variations: Vec<Vec<PgnMove>>,
+}
+
+/// Names and default values of the Seven Tag Roster, in the order they must be exported.
+const SEVEN_TAG_ROSTER: [(&str, &str); 7] = [
+    ("Event", "?"),
+    ("Site", "?"),
+    ("Date", "????.??.??"),
+    ("Round", "?"),
+    ("White", "?"),
+    ("Black", "?"),
+    ("Result", "*"),
+];
+
+/// Maximal length of a movetext line in the export format.
+const MAX_LINE_LENGTH: usize = 80;
+
+/// Suffix annotations and the numeric annotation glyphs they stand for.
+const SUFFIX_ANNOTATIONS: [(&str, u8); 6] = [
+    ("!", 1),
+    ("?", 2),
+    ("!!", 3),
+    ("??", 4),
+    ("!?", 5),
+    ("?!", 6),
+];
+
+/// Writes a game in the PGN export format.
+///
+/// The Seven Tag Roster is always present, missing tags are filled with default values,
+/// and the `Result` tag is derived from the `result` argument.
+/// If `start` is not the initial position, it is recorded with the `SetUp` and `FEN` tags.
+/// The `comment` precedes the first move.
+pub(crate) fn write_pgn(
+    tags: &[(String, String)],
+    start: &Board,
+    comment: Option<&str>,
+    moves: &[PgnMove],
+    result: GameStatus,
+) -> String {
+    let find_tag = |name: &str| {
+        tags.iter()
+            .find_map(|(n, v)| if n == name { Some(v.as_str()) } else { None })
+    };
+
+    let result = result.to_string();
+    let mut pgn = String::new();
+
+    for (name, default) in SEVEN_TAG_ROSTER {
+        let value = if name == "Result" {
+            &result
+        } else {
+            find_tag(name).unwrap_or(default)
+        };
+        write_tag(&mut pgn, name, value);
+    }
+
+    for (name, value) in tags {
+        let is_exported =
+            SEVEN_TAG_ROSTER.iter().any(|(n, _)| n == name) || name == "SetUp" || name == "FEN";
+        if !is_exported {
+            write_tag(&mut pgn, name, value);
+        }
+    }
+
+    let fen = start.as_fen();
+    if fen != Board::initial().as_fen() {
+        write_tag(&mut pgn, "SetUp", "1");
+        write_tag(&mut pgn, "FEN", &fen);
+    }
+
+    pgn.push('\n');
+
+    let mut tokens = vec![];
+    if let Some(comment) = comment {
+        write_comment(&mut tokens, comment);
+    }
+    write_movetext(&mut tokens, moves, start.raw().move_number, start.side());
+    tokens.push(result);
+
+    let mut line = String::new();
+    for token in tokens {
+        if !line.is_empty() && line.len() + 1 + token.len() > MAX_LINE_LENGTH {
+            pgn.push_str(&line);
+            pgn.push('\n');
+            line.clear();
+        }
+        if !line.is_empty() {
+            line.push(' ');
+        }
+        line.push_str(&token);
+    }
+    pgn.push_str(&line);
+    pgn.push('\n');
+
+    pgn
+}
+
+/// Splits a line of moves into tokens of the movetext.
+/// A move number is kept together with its move, so that they are never split between lines.
+fn write_movetext(
+    tokens: &mut Vec<String>,
+    moves: &[PgnMove],
+    mut move_number: u16,
+    mut side: Color,
+) {
+    // Black's move requires the number indication
+    // at the beginning of a line and right after a variation.
+    let mut is_number_required = true;
+
+    for PgnMove {
+        san,
+        nags,
+        comment,
+        variations,
+    } in moves
+    {
+        tokens.push(match side {
+            Color::White => format!("{move_number}. {san}"),
+            Color::Black if is_number_required => format!("{move_number}... {san}"),
+            Color::Black => san.clone(),
+        });
+        is_number_required = false;
+
+        tokens.extend(nags.iter().map(|nag| format!("${nag}")));
+        if let Some(comment) = comment {
+            write_comment(tokens, comment);
+            is_number_required = true;
+        }
+
+        for variation in variations {
+            let first = tokens.len();
+            write_movetext(tokens, variation, move_number, side);
+
+            if tokens.len() > first {
+                tokens[first].insert(0, '(');
+                tokens
+                    .last_mut()
+                    .expect("Variation has at least 1 token")
+                    .push(')');
+                is_number_required = true;
+            }
+        }
+
+        if side == Color::Black {
+            move_number += 1;
+        }
+        side = side.inv();
+    }
+}
+
+/// Splits a comment into words, so that it can be wrapped between lines.
+/// A closing brace cannot be escaped in a comment and is dropped.
+fn write_comment(tokens: &mut Vec<String>, comment: &str) {
+    let comment = comment.replace('}', "");
+    let mut words = comment.split_whitespace().map(str::to_string);
+
+    let Some(first) = words.next() else {
+        return;
+    };
+
+    tokens.push(format!("{{{first}"));
+    tokens.extend(words);
+    tokens
+        .last_mut()
+        .expect("Comment has at least 1 word")
+        .push('}');
+}
+
+fn write_tag(pgn: &mut String, name: &str, value: &str) {
+    let value = value.replace('\\', "\\\\").replace('"', "\\\"");
+    writeln!(pgn, "[{name} \"{value}\"]").expect("Writing to a String cannot fail");
+}
+
+impl FromStr for Pgn {
+    type Err = HistoricalBoardError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut tags = vec![];
+        let mut comment = None;
+        let mut result = GameStatus::Running;
+
+        // Lines of moves being parsed: the main line followed by the nested variations.
+        let mut lines = vec![Line {
+            moves: vec![],
+            first_ply: 1,
+        }];
+
+        for token in Lexer::new(s) {
+            let is_main_line = lines.len() == 1;
+            let is_movetext_started = !lines[0].moves.is_empty();
+
+            let line = lines.last_mut().expect("Main line is always present");
+            // Ply the token is associated with.
+            let ply = line.first_ply + line.moves.len();
+
+            let error = |token: &str| HistoricalBoardError::Pgn {
+                ply,
+                token: token.to_string(),
+            };
+
+            match token {
+                Token::Tag { name, value } if is_main_line && !is_movetext_started => {
+                    tags.push((name, value));
+                }
+                Token::Tag { name, .. } => return Err(error(&format!("[{name}"))),
+                Token::Comment(text) => match line.moves.last_mut() {
+                    Some(m) => append_comment(&mut m.comment, &text),
+                    None if is_main_line => append_comment(&mut comment, &text),
+                    None => {
+                        warn!("Comment {{{text}}} preceding a variation at ply {ply} is dropped")
+                    }
+                },
+                Token::Nag(nag) => match line.moves.last_mut() {
+                    Some(m) => m.nags.push(nag),
+                    None => return Err(error(&format!("${nag}"))),
+                },
+                Token::VariationStart => {
+                    // A variation is an alternative to the last move of the current line.
+                    if line.moves.is_empty() {
+                        return Err(error("("));
+                    }
+                    let first_ply = ply - 1;
+                    lines.push(Line {
+                        moves: vec![],
+                        first_ply,
+                    });
+                }
+                Token::VariationEnd => {
+                    if is_main_line {
+                        return Err(error(")"));
+                    }
+                    let variation = lines.pop().expect("Variation is present").moves;
+                    lines
+                        .last_mut()
+                        .and_then(|line| line.moves.last_mut())
+                        .expect("Variation follows a move")
+                        .variations
+                        .push(variation);
+                }
+                Token::Symbol(symbol) => match parse_symbol(&symbol) {
+                    Symbol::MoveNumber => {}
+                    Symbol::Result(status) if is_main_line => {
+                        result = status;
+                        // Anything after the termination marker belongs to the next game.
+                        break;
+                    }
+                    Symbol::San { san, nag } => line.moves.push(PgnMove {
+                        san,
+                        nags: nag.into_iter().collect(),
+                        ..PgnMove::default()
+                    }),
+                    Symbol::Result(_) | Symbol::Invalid => return Err(error(&symbol)),
+                },
+                Token::Invalid(token) => return Err(error(&token)),
+            }
+        }
+
+        if lines.len() > 1 {
+            let line = lines.last().expect("Variation is present");
+            return Err(HistoricalBoardError::Pgn {
+                ply: line.first_ply + line.moves.len(),
+                token: "(".to_string(),
+            });
+        }
+
+        Ok(Pgn {
+            tags,
+            comment,
+            moves: lines.pop().expect("Main line is always present").moves,
+            result,
+        })
+    }
+}
+
+/// Appends the `text` to the comment, which might have been split by other tokens.
+fn append_comment(comment: &mut Option<String>, text: &str) {
+    let text = text.split_whitespace().collect::<Vec<_>>().join(" ");
+    if text.is_empty() {
+        return;
+    }
+
+    match comment {
+        Some(comment) => {
+            comment.push(' ');
+            comment.push_str(&text);
+        }
+        None => *comment = Some(text),
+    }
+}
+
+/// Line of moves under construction.
+struct Line {
+    moves: Vec<PgnMove>,
+    /// Ply of the first move in the line.
+    first_ply: usize,
+}
+
+/// Lexical element of PGN.
+#[derive(Debug, PartialEq)]
+enum Token {
+    /// Tag pair, e.g., `[Event "Casual game"]`.
+    Tag {
+        name: String,
+        value: String,
+    },
+    /// Move number, SAN move or game termination marker.
+    Symbol(String),
+    /// Numeric annotation glyph, e.g., `$1`.
+    Nag(u8),
+    /// Text of a brace or rest-of-line comment.
+    Comment(String),
+    VariationStart,
+    VariationEnd,
+    /// Character sequence which does not form a valid token.
+    Invalid(String),
+}
+
+struct Lexer<'a> {
+    source: &'a str,
+    chars: Peekable<CharIndices<'a>>,
+}
+
+impl<'a> Lexer<'a> {
+    fn new(source: &'a str) -> Self {
+        Self {
+            source,
+            chars: source.char_indices().peekable(),
+        }
+    }
+
+    /// Consumes characters while the predicate holds and returns the slice from `start`
+    /// to the last consumed character. Characters before `start` might have been consumed already.
+    fn take_while(&mut self, start: usize, predicate: impl Fn(char) -> bool) -> &'a str {
+        let mut end = self
+            .chars
+            .peek()
+            .map_or(self.source.len(), |&(i, _)| i)
+            .max(start);
+        while let Some(&(i, c)) = self.chars.peek() {
+            if !predicate(c) {
+                break;
+            }
+            end = i + c.len_utf8();
+            self.chars.next();
+        }
+        &self.source[start..end]
+    }
+
+    fn skip_whitespace(&mut self) {
+        while self.chars.next_if(|(_, c)| c.is_whitespace()).is_some() {}
+    }
+
+    /// Parses the remainder of a tag pair after the opening bracket.
+    fn tag(&mut self) -> Token {
+        self.skip_whitespace();
+        let Some(&(start, _)) = self.chars.peek() else {
+            return Token::Invalid("[".to_string());
+        };
+        let name = self
+            .take_while(start, |c| c.is_alphanumeric() || c == '_')
+            .to_string();
+
+        self.skip_whitespace();
+        if self.chars.next_if(|(_, c)| *c == '"').is_none() {
+            return Token::Invalid(format!("[{name}"));
+        }
+
+        let mut value = String::new();
+        loop {
+            match self.chars.next() {
+                Some((_, '\\')) => match self.chars.next() {
+                    Some((_, c)) => value.push(c),
+                    None => return Token::Invalid(format!("[{name} \"{value}")),
+                },
+                Some((_, '"')) => break,
+                Some((_, c)) => value.push(c),
+                None => return Token::Invalid(format!("[{name} \"{value}")),
+            }
+        }
+
+        self.skip_whitespace();
+        if self.chars.next_if(|(_, c)| *c == ']').is_none() {
+            return Token::Invalid(format!("[{name} \"{value}\""));
+        }
+
+        Token::Tag { name, value }
+    }
+}
+
+impl Iterator for Lexer<'_> {
+    type Item = Token;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            self.skip_whitespace();
+            let (i, c) = self.chars.next()?;
+
+            return Some(match c {
+                '[' => self.tag(),
+                '{' => {
+                    let text = self.take_while(i + 1, |c| c != '}');
+                    if self.chars.next().is_none() {
+                        return Some(Token::Invalid("{".to_string()));
+                    }
+                    Token::Comment(text.to_string())
+                }
+                ';' => Token::Comment(self.take_while(i + 1, |c| c != '\n').to_string()),
+                // Escape mechanism: the line is ignored.
+                '%' if i == 0 || self.source[..i].ends_with('\n') => {
+                    self.take_while(i, |c| c != '\n');
+                    continue;
+                }
+                '$' => {
+                    let digits = self.take_while(i + 1, |c| c.is_ascii_digit());
+                    match digits.parse() {
+                        Ok(nag) => Token::Nag(nag),
+                        Err(_) => Token::Invalid(format!("${digits}")),
+                    }
+                }
+                '(' => Token::VariationStart,
+                ')' => Token::VariationEnd,
+                c if c.is_alphanumeric() || c == '*' => Token::Symbol(
+                    self.take_while(i, |c| c.is_alphanumeric() || "_+#=:-/.!?*".contains(c))
+                        .to_string(),
+                ),
+                c => Token::Invalid(c.to_string()),
+            });
+        }
+    }
+}
+
+/// Interpretation of [`Token::Symbol`].
+enum Symbol {
+    MoveNumber,
+    Result(GameStatus),
+    /// SAN move with the numeric annotation glyph of its suffix annotation, if any.
+    San {
+        san: String,
+        nag: Option<u8>,
+    },
+    Invalid,
+}
+
+fn parse_symbol(symbol: &str) -> Symbol {
+    match symbol {
+        "1-0" => return Symbol::Result(GameStatus::White),
+        "0-1" => return Symbol::Result(GameStatus::Black),
+        "1/2-1/2" => return Symbol::Result(GameStatus::Draw),
+        "*" => return Symbol::Result(GameStatus::Running),
+        _ => {}
+    }
+
+    // Move number indications might be glued to the move, e.g., `1.e4` or `3...Nf6`.
+    let digits = symbol.trim_start_matches(|c: char| c.is_ascii_digit());
+    let san = if digits.len() < symbol.len() && digits.starts_with('.') {
+        digits.trim_start_matches('.')
+    } else {
+        symbol
+    };
+
+    if san.is_empty() {
+        return Symbol::MoveNumber;
+    }
+
+    // Suffix annotations, e.g., `e4!?`, are not a part of SAN.
+    let move_text = san.trim_end_matches(['!', '?']);
+    let suffix = &san[move_text.len()..];
+    let nag = match SUFFIX_ANNOTATIONS.iter().find(|(s, _)| *s == suffix) {
+        Some(&(_, nag)) => Some(nag),
+        None if suffix.is_empty() => None,
+        None => return Symbol::Invalid,
+    };
+
+    // Castling is often written with zeros instead of the letter O.
+    let san = match move_text.strip_prefix("0-0") {
+        Some(rest) if rest.is_empty() || rest.starts_with(['-', '+', '#']) => {
+            move_text.replace('0', "O")
+        }
+        _ => move_text.to_string(),
+    };
+
+    if san.is_empty() || san.starts_with(|c: char| c.is_ascii_digit()) || san.contains('.') {
+        Symbol::Invalid
+    } else {
+        Symbol::San { san, nag }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::history::HistoricalBoard;
+
+    fn san(san: &str) -> PgnMove {
+        PgnMove {
+            san: san.to_string(),
+            ..PgnMove::default()
+        }
+    }
+
+    fn pgn_error(pgn: &str) -> (usize, String) {
+        match Pgn::from_str(pgn) {
+            Err(HistoricalBoardError::Pgn { ply, token }) => (ply, token),
+            other => panic!("PGN error is expected, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn lexer_splits_tokens() {
+        let tokens = Lexer::new(
+            "[Event \"A \\\"quoted\\\" name\"]\n1.e4 {best by test} $1 ; rest of line\n(1... c5) *",
+        )
+        .collect::<Vec<_>>();
+
+        assert_eq!(
+            tokens,
+            vec![
+                Token::Tag {
+                    name: "Event".to_string(),
+                    value: "A \"quoted\" name".to_string(),
+                },
+                Token::Symbol("1.e4".to_string()),
+                Token::Comment("best by test".to_string()),
+                Token::Nag(1),
+                Token::Comment(" rest of line".to_string()),
+                Token::VariationStart,
+                Token::Symbol("1...".to_string()),
+                Token::Symbol("c5".to_string()),
+                Token::VariationEnd,
+                Token::Symbol("*".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn lexer_skips_escaped_lines_and_reports_invalid_tokens() {
+        let tokens = Lexer::new("% escaped line\ne4 {unterminated").collect::<Vec<_>>();
+
+        assert_eq!(
+            tokens,
+            vec![
+                Token::Symbol("e4".to_string()),
+                Token::Invalid("{".to_string()),
+            ]
+        );
+        assert_eq!(
+            Lexer::new("$300").collect::<Vec<_>>(),
+            vec![Token::Invalid("$300".to_string())]
+        );
+    }
+
+    #[test]
+    fn castling_with_zeros_is_read_as_castling() {
+        let pgn = Pgn::from_str("1. e4 e5 2. Nf3 Nf6 3. Bc4 Bc5 4. 0-0 0-0 *").unwrap();
+
+        assert_eq!(pgn.moves[6].san, "O-O");
+        assert_eq!(pgn.moves[7].san, "O-O");
+        assert!(matches!(
+            parse_symbol("0-0-0+"),
+            Symbol::San { san, nag: None } if san == "O-O-O+"
+        ));
+
+        let board =
+            HistoricalBoard::from_pgn("1. e4 e5 2. Nf3 Nc6 3. Bc4 Bc5 4. 0-0 Nf6 *", None).unwrap();
+        assert_eq!(board.previous_san().as_deref(), Some("Nf6"));
+    }
+
+    #[test]
+    fn symbols_are_interpreted() {
+        assert!(matches!(parse_symbol("12."), Symbol::MoveNumber));
+        assert!(matches!(
+            parse_symbol("1/2-1/2"),
+            Symbol::Result(GameStatus::Draw)
+        ));
+        assert!(matches!(
+            parse_symbol("3...Nf6!?"),
+            Symbol::San { san, nag: Some(5) } if san == "Nf6"
+        ));
+        assert!(matches!(parse_symbol("e4!!!"), Symbol::Invalid));
+        assert!(matches!(parse_symbol("4e4"), Symbol::Invalid));
+    }
+
+    #[test]
+    fn game_is_parsed() {
+        let pgn = Pgn::from_str(
+            "[White \"Morphy\"]\n[Black \"?\"]\n\n{Opening} 1. e4 e5! $14 {Solid} (1... c5 {Sicilian}) 2. Nf3 1-0",
+        )
+        .unwrap();
+
+        assert_eq!(
+            pgn,
+            Pgn {
+                tags: vec![
+                    ("White".to_string(), "Morphy".to_string()),
+                    ("Black".to_string(), "?".to_string()),
+                ],
+                comment: Some("Opening".to_string()),
+                moves: vec![
+                    san("e4"),
+                    PgnMove {
+                        nags: vec![1, 14],
+                        comment: Some("Solid".to_string()),
+                        variations: vec![vec![PgnMove {
+                            comment: Some("Sicilian".to_string()),
+                            ..san("c5")
+                        }]],
+                        ..san("e5")
+                    },
+                    san("Nf3"),
+                ],
+                result: GameStatus::White,
+            }
+        );
+    }
+
+    #[test]
+    fn errors_report_ply_and_token() {
+        assert_eq!(pgn_error("1. e4 ) e5"), (2, ")".to_string()));
+        assert_eq!(pgn_error("1. e4 (1. d4 d5"), (3, "(".to_string()));
+        assert_eq!(pgn_error("( 1. e4 )"), (1, "(".to_string()));
+        assert_eq!(pgn_error("1. e4 [Event \"x\"]"), (2, "[Event".to_string()));
+        assert_eq!(pgn_error("$1 1. e4"), (1, "$1".to_string()));
+        assert_eq!(
+            pgn_error("1. e4 e5 2. Nf3 (2. d4 0-1) *"),
+            (4, "0-1".to_string())
+        );
+        assert_eq!(pgn_error("1. e4 e5 2. @"), (3, "@".to_string()));
+
+        match HistoricalBoard::from_pgn("1. e4 e5 2. Qxf7 *", None) {
+            Err(HistoricalBoardError::Pgn { ply, token }) => {
+                assert_eq!((ply, token.as_str()), (3, "Qxf7"));
+            }
+            other => panic!("Illegal move is expected, got {:?}", other.err()),
+        }
+        match HistoricalBoard::from_pgn("1. e4 e5 (1... e6 2. Ke3) *", None) {
+            Err(HistoricalBoardError::Pgn { ply, token }) => {
+                assert_eq!((ply, token.as_str()), (3, "Ke3"));
+            }
+            other => panic!("Illegal move is expected, got {:?}", other.err()),
+        }
+    }
+
+    #[test]
+    fn seven_tag_roster_and_setup_are_written() {
+        let start = Board::from_str("4k3/8/8/8/8/8/4P3/4K3 b - - 0 30").expect("Position is valid");
+        let tags = vec![
+            ("Annotator".to_string(), "Me".to_string()),
+            ("White".to_string(), "A \"B\" C".to_string()),
+            ("Result".to_string(), "1-0".to_string()),
+        ];
+
+        let pgn = write_pgn(
+            &tags,
+            &start,
+            None,
+            &[san("Kd7"), san("e4")],
+            GameStatus::Running,
+        );
+
+        assert_eq!(
+            pgn,
+            "[Event \"?\"]\n[Site \"?\"]\n[Date \"????.??.??\"]\n[Round \"?\"]\n\
+             [White \"A \\\"B\\\" C\"]\n[Black \"?\"]\n[Result \"*\"]\n[Annotator \"Me\"]\n\
+             [SetUp \"1\"]\n[FEN \"4k3/8/8/8/8/8/4P3/4K3 b - - 0 30\"]\n\n30... Kd7 31. e4 *\n"
+        );
+    }
+
+    #[test]
+    fn movetext_is_numbered_and_wrapped() {
+        let moves = vec![
+            san("e4"),
+            PgnMove {
+                nags: vec![2],
+                comment: Some("A rather long comment, which has to be wrapped across the lines of the movetext".to_string()),
+                variations: vec![vec![san("c5"), san("Nf3")]],
+                ..san("e5")
+            },
+            san("Nf3"),
+            san("Nc6"),
+        ];
+
+        let pgn = write_pgn(
+            &[],
+            &Board::initial(),
+            Some("Start"),
+            &moves,
+            GameStatus::Draw,
+        );
+        let movetext = pgn.split("\n\n").nth(1).unwrap();
+
+        assert!(movetext.lines().all(|line| line.len() <= MAX_LINE_LENGTH));
+        assert_eq!(
+            movetext.split_whitespace().collect::<Vec<_>>().join(" "),
+            "{Start} 1. e4 e5 $2 {A rather long comment, which has to be wrapped across the lines \
+             of the movetext} (1... c5 2. Nf3) 2. Nf3 Nc6 1/2-1/2"
+        );
+    }
+
+    #[test]
+    fn game_round_trips() {
+        let source =
+            "[Event \"Casual\"]\n\n{Start} 1. e4 e5 (1... c5 $1 2. Nf3 (2. c3 {Alapin}) 2... d6) \
+                      2. Nf3 {Develops} Nc6 3. Bb5 a6 4. Ba4 Nf6 5. O-O Be7 6. Re1 b5 7. Bb3 d6 \
+                      8. c3 O-O 9. h3 Nb8 10. d4 Nbd7 *";
+
+        let pgn = Pgn::from_str(source).unwrap();
+        let written = write_pgn(
+            &pgn.tags,
+            &Board::initial(),
+            pgn.comment.as_deref(),
+            &pgn.moves,
+            pgn.result,
+        );
+        let reread = Pgn::from_str(&written).unwrap();
+
+        assert_eq!(reread.comment, pgn.comment);
+        assert_eq!(reread.moves, pgn.moves);
+        assert_eq!(reread.result, pgn.result);
+        assert_eq!(reread.tag("Event"), Some("Casual"));
+
+        let board = HistoricalBoard::from_pgn(source, None).unwrap();
+        assert_eq!(
+            HistoricalBoard::from_pgn(&board.to_pgn(), None)
+                .unwrap()
+                .to_pgn(),
+            board.to_pgn()
+        );
+        assert_eq!(Pgn::from_str(&board.to_pgn()).unwrap().moves, pgn.moves);
+    }
+}
@@ -0,0 +1,63 @@
+use crate::history::Termination;
+use crate::Shape;
+use owlchess::{Color, GameStatus};
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+/// Complete game on a board: the starting position, all moves with their variations and annotations,
+/// the active variation, the current position and the shapes drawn over the board.
+///
+/// Export it with [`crate::Action::export_state`] and restore it with [`crate::Action::restore`].
+/// Running clocks are not a part of the game, the restored game starts them afresh.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BoardState {
+    /// Starting position in FEN notation.
+    pub(super) fen: String,
+    /// Comment of the game.
+    #[serde(default)]
+    pub(super) comment: Option<String>,
+    /// Moves played in the starting position, the first one belongs to the main line.
+    pub(super) continuations: Vec<MoveState>,
+    /// Indices of the continuations selected in every position along the active variation.
+    pub(super) path: Vec<usize>,
+    /// Number of moves along the active variation leading to the current position.
+    pub(super) step_pointer: usize,
+    pub(super) tags: Vec<(String, String)>,
+    #[serde(with = "crate::serialization::status")]
+    pub(super) result: GameStatus,
+    pub(super) agreement: Option<Agreement>,
+    #[serde(with = "crate::serialization::optional_side")]
+    pub(super) draw_offer: Option<Color>,
+    #[serde(default)]
+    pub(super) shapes: Vec<Shape>,
+}
+
+impl BoardState {
+    /// Shapes drawn over the board.
+    pub(crate) fn shapes(&self) -> &[Shape] {
+        &self.shapes
+    }
+}
+
+/// Move together with all moves played after it.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub(super) struct MoveState {
+    /// Move in UCI notation.
+    pub(super) uci: String,
+    /// Remaining time of the side which has played the move, if the game is played with clocks.
+    pub(super) clock: Option<Duration>,
+    /// Numeric annotation glyphs of the move.
+    #[serde(default)]
+    pub(super) nags: Vec<u8>,
+    #[serde(default)]
+    pub(super) comment: Option<String>,
+    pub(super) continuations: Vec<MoveState>,
+}
+
+/// Game ended by the players, see [`Termination::Resignation`] and [`Termination::DrawAgreement`].
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub(super) struct Agreement {
+    #[serde(with = "crate::serialization::status")]
+    pub(super) outcome: GameStatus,
+    pub(super) reason: Termination,
+}
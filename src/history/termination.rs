@@ -0,0 +1,61 @@
+use owlchess::{DrawReason, Outcome, WinReason};
+use std::fmt::Display;
+
+/// Reason the game has ended.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum Termination {
+    Checkmate,
+    Stalemate,
+    /// Neither side has enough pieces to checkmate.
+    InsufficientMaterial,
+    /// No capture or pawn move has been made during the last 50 moves by each side.
+    FiftyMoveRule,
+    /// The same position has occurred three times with the same side to move.
+    ThreefoldRepetition,
+    /// The side to move has run out of time.
+    Timeout,
+    Resignation,
+    /// The players have agreed to a draw.
+    DrawAgreement,
+}
+
+impl Termination {
+    /// Interprets an [`Outcome`] calculated by [`owlchess`].
+    /// Outcomes which cannot arise from a position on the board, e.g., resignation, are not recognised.
+    pub(crate) fn from_outcome(outcome: Outcome) -> Option<Self> {
+        match outcome {
+            Outcome::Win {
+                reason: WinReason::Checkmate,
+                ..
+            } => Some(Self::Checkmate),
+            Outcome::Win {
+                reason: WinReason::TimeForfeit,
+                ..
+            } => Some(Self::Timeout),
+            Outcome::Draw(DrawReason::Stalemate) => Some(Self::Stalemate),
+            Outcome::Draw(DrawReason::InsufficientMaterial) => Some(Self::InsufficientMaterial),
+            Outcome::Draw(DrawReason::Moves50 | DrawReason::Moves75) => Some(Self::FiftyMoveRule),
+            Outcome::Draw(DrawReason::Repeat3 | DrawReason::Repeat5) => {
+                Some(Self::ThreefoldRepetition)
+            }
+            _ => None,
+        }
+    }
+}
+
+impl Display for Termination {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Checkmate => write!(f, "checkmate"),
+            Self::Stalemate => write!(f, "stalemate"),
+            Self::InsufficientMaterial => write!(f, "insufficient material"),
+            Self::FiftyMoveRule => write!(f, "fifty-move rule"),
+            Self::ThreefoldRepetition => write!(f, "threefold repetition"),
+            Self::Timeout => write!(f, "timeout"),
+            Self::Resignation => write!(f, "resignation"),
+            Self::DrawAgreement => write!(f, "draw agreement"),
+        }
+    }
+}
@@ -0,0 +1,43 @@
+use dioxus::prelude::Key;
+use owlchess::{Cell, Coord, File, Piece, Rank};
+
+/// Square focused with the keyboard.
+/// Before any square is focused, the bottom left one is reachable with the Tab key.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub(crate) struct KeyboardFocus(pub Option<Coord>);
+
+/// Moves the `focus` with an arrow `key` in the direction it points to on the screen.
+/// `files` and `ranks` are listed in the order they are displayed, i.e., left to right and top to bottom.
+/// Returns `None` if the key is not an arrow key.
+pub(crate) fn move_focus(focus: Coord, key: &Key, files: &[File], ranks: &[Rank]) -> Option<Coord> {
+    let column = files.iter().position(|&f| f == focus.file())?;
+    let row = ranks.iter().position(|&r| r == focus.rank())?;
+
+    let (column, row) = match key {
+        Key::ArrowLeft => (column.saturating_sub(1), row),
+        Key::ArrowRight => ((column + 1).min(files.len() - 1), row),
+        Key::ArrowUp => (column, row.saturating_sub(1)),
+        Key::ArrowDown => (column, (row + 1).min(ranks.len() - 1)),
+        _ => return None,
+    };
+
+    Some(Coord::from_parts(files[column], ranks[row]))
+}
+
+/// Accessible name of a square, e.g., "e4, white knight" or "e5, empty".
+pub(crate) fn square_label(coord: Coord, cell: Cell) -> String {
+    let (Some(color), Some(piece)) = (cell.color(), cell.piece()) else {
+        return format!("{coord}, empty");
+    };
+
+    let piece = match piece {
+        Piece::Pawn => "pawn",
+        Piece::King => "king",
+        Piece::Knight => "knight",
+        Piece::Bishop => "bishop",
+        Piece::Rook => "rook",
+        Piece::Queen => "queen",
+    };
+
+    format!("{coord}, {} {piece}", color.as_long_str())
+}
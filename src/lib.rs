@@ -1,46 +1,150 @@
 mod chessboard;
+mod clock;
+mod controlled;
+mod drag;
+mod editor;
+mod engine;
 pub(crate) mod files;
 mod history;
+mod keyboard;
 pub(crate) mod move_builder;
+#[cfg(feature = "persist")]
+mod persist;
 pub(crate) mod piece;
 mod pieces;
+mod premove;
 pub(crate) mod promotion;
+mod puzzle;
 pub(crate) mod ranks;
+#[cfg(feature = "serde")]
+mod serialization;
+mod shapes;
 mod square;
+#[cfg(feature = "sync")]
+mod sync;
+mod theme;
+mod transport;
 
-pub use chessboard::{Action, Chessboard, ChessboardProps, SanMove};
-pub use history::BoardAction;
-pub use owlchess::Color;
-pub use pieces::PieceSet;
+pub use chessboard::{
+    use_chessboard, Action, Chessboard, ChessboardController, ChessboardProps, SanMove,
+};
+pub use clock::{Bonus, ClockSource, ClockSourceHandle, ManualClock, SystemClock, TimeControl};
+pub use controlled::ControlledPosition;
+#[cfg(feature = "desktop")]
+pub use engine::ProcessTransport;
+pub use engine::{Analysis, Evaluation, UciEngine};
+#[cfg(feature = "serde")]
+pub use history::BoardState;
+pub use history::{BoardAction, HistoricalBoardError, Termination};
+pub use owlchess::{Color, GameStatus, Piece};
+pub use pieces::{CustomPieces, PieceImage, PieceSet, PieceTheme};
+pub use shapes::{Shape, ShapeColor};
+#[cfg(all(feature = "sync", feature = "web"))]
+pub use sync::WebSocketTransport;
+#[cfg(feature = "sync")]
+pub use sync::{BoardSync, LoopbackTransport, SyncMessage};
+pub use theme::BoardTheme;
+pub use transport::LineTransport;
 
 use crate::history::HistoricalBoard;
-use crate::move_builder::{MoveAction, MoveBuilder};
+use crate::move_builder::{MoveAction, MoveBuilder, PlayerMoves};
+use crate::puzzle::{Puzzle, Verdict};
 use dioxus::prelude::{Readable, Signal, Writable};
 use owlchess::board::PrettyStyle;
-use tracing::debug;
+use owlchess::Move;
+use tracing::{debug, warn};
 
 /// Tries finalizing the state of [`MoveBuilder`] and apply the [`owlchess::Move`].
-fn finalize(move_builder: &mut Signal<MoveBuilder>, board: &mut Signal<HistoricalBoard>) {
+/// Moves of the player are either applied, kept pending until the host confirms them,
+/// or only proposed to the host controlling the board.
+fn finalize(
+    move_builder: &mut Signal<MoveBuilder>,
+    board: &mut Signal<HistoricalBoard>,
+    puzzle: &mut Signal<Option<Puzzle>>,
+    player_moves: PlayerMoves,
+) {
     let finalized = move_builder.write().finalize();
 
-    match finalized {
-        MoveAction::Apply(m) => {
-            board.write().make_move(m).expect("Move must be valid");
-            debug!("New board\n{}", board.read());
+    match (finalized, player_moves) {
+        (MoveAction::Apply { m, by_player: true }, PlayerMoves::Proposed) => {
+            debug!("Move {m} is proposed to the host");
+            propose(&board.read(), m);
         }
-        MoveAction::Revert => {
+        // Moves of a puzzle are judged by the board itself.
+        (MoveAction::Apply { m, by_player: true }, PlayerMoves::Confirmed { timeout })
+            if puzzle.read().is_none() =>
+        {
+            let deadline = board.read().clock_source().now() + timeout;
+            move_builder.write().propose(m, deadline);
+
+            debug!("Move {m} waits for the confirmation of the host");
+            propose(&board.read(), m);
+        }
+        (MoveAction::Apply { m, .. }, _) => apply_move(m, move_builder, board, puzzle),
+        (MoveAction::Revert, _) => {
             let m = board.write().revert_last_move();
             debug!(
                 "Move {m:?} has been reverted \nNew board\n{}\n",
                 board.read().pretty(PrettyStyle::Utf8)
             );
         }
-        MoveAction::StepBack => {
+        (MoveAction::StepBack, _) => {
             board.write().step_back();
         }
-        MoveAction::StepForward => {
+        (MoveAction::StepForward, _) => {
             board.write().step_forward();
         }
-        MoveAction::None => {}
+        (MoveAction::None, _) => {}
+    }
+}
+
+/// Applies the [`owlchess::Move`] to the board.
+/// Moves played while a [`Puzzle`] is loaded are judged before they are applied.
+fn apply_move(
+    m: Move,
+    move_builder: &mut Signal<MoveBuilder>,
+    board: &mut Signal<HistoricalBoard>,
+    puzzle: &mut Signal<Option<Puzzle>>,
+) {
+    let verdict = puzzle
+        .write()
+        .as_mut()
+        .map_or(Verdict::Accepted, |puzzle| puzzle.check(&board.read(), m));
+
+    if let Verdict::Wrong { expected } = verdict {
+        let board = board.read();
+        debug!("Puzzle failed: {m} is played instead of {expected}");
+
+        if let Some(tx) = board.move_tx.as_ref() {
+            tx.send(BoardAction::PuzzleFailed {
+                played: SanMove::from_move(&board, m),
+                expected: SanMove::from_move(&board, expected),
+            });
+        }
+        return;
+    }
+
+    // A legal move is only refused after the flag has fallen.
+    if let Err(err) = board.write().make_move(m) {
+        warn!("Move {m} cannot be made: {err}");
+        return;
+    }
+    debug!("New board\n{}", board.read());
+
+    match verdict {
+        Verdict::Correct { reply } => move_builder.write().apply_move(reply),
+        Verdict::Solved => {
+            if let Some(tx) = board.read().move_tx.as_ref() {
+                tx.send(BoardAction::PuzzleSolved);
+            }
+        }
+        Verdict::Wrong { .. } | Verdict::Accepted => {}
+    }
+}
+
+/// Reports a move of the player which is not applied until the host accepts it.
+fn propose(board: &HistoricalBoard, m: Move) {
+    if let Some(tx) = board.move_tx.as_ref() {
+        tx.send(BoardAction::MoveProposed(SanMove::from_move(board, m)));
     }
 }
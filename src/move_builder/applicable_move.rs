@@ -14,6 +14,9 @@ pub enum ApplicableMove {
 
     Previous(Move),
     Next(Move),
+    /// [`Move`] of the player rejected by the host, its pieces return to their squares.
+    /// It shall never be applied to a [`Board`].
+    Rollback(Move),
 }
 
 impl ApplicableMove {
@@ -24,6 +27,7 @@ impl ApplicableMove {
             Self::Revert(m) => m.src(),
             Self::Previous(m) => m.src(),
             Self::Next(m) => m.src(),
+            Self::Rollback(m) => m.src(),
         }
     }
 
@@ -34,6 +38,7 @@ impl ApplicableMove {
             Self::Revert(m) => m.dst(),
             Self::Previous(m) => m.dst(),
             Self::Next(m) => m.dst(),
+            Self::Rollback(m) => m.dst(),
         }
     }
 
@@ -41,6 +46,7 @@ impl ApplicableMove {
         match self {
             Self::Automatic(m) => vec![(m.src(), m.dst())],
             Self::Revert(m) | Self::Previous(m) | Self::Next(m) => {
+                #[allow(clippy::match_single_binding)] // Move kinds are to be handled separately.
                 let animations = match m.kind() {
                     // MoveKind::CastlingKingside => {}
                     // MoveKind::CastlingQueenside => {}
@@ -56,34 +62,41 @@ impl ApplicableMove {
                 debug!("Reverting/going back/advancing {m:?}, animation: {animations:?}");
                 animations
             }
-            Self::Manual(m) => {
-                fn coord(f: File, r: Rank) -> Coord {
-                    Coord::from_parts(f, r)
-                }
+            Self::Manual(m) => move_animations(*m),
+            // Pieces are moved from their destinations back to the source squares.
+            Self::Rollback(m) => move_animations(*m)
+                .into_iter()
+                .map(|(src, _)| (src, src))
+                .collect(),
+        }
+    }
+}
 
-                // Compute supporting animations.
-                match m.kind() {
-                    MoveKind::CastlingKingside => {
-                        // King steps from the E to G file.
-                        // Rook steps from the H to F file.
-                        let rank = m.src().rank();
-                        vec![
-                            (coord(File::E, rank), coord(File::G, rank)),
-                            (coord(File::H, rank), coord(File::F, rank)),
-                        ]
-                    }
-                    MoveKind::CastlingQueenside => {
-                        // King steps from the E to C file.
-                        // Rook steps from the A to D file.
-                        let rank = m.src().rank();
-                        vec![
-                            (coord(File::E, rank), coord(File::C, rank)),
-                            (coord(File::A, rank), coord(File::D, rank)),
-                        ]
-                    }
-                    _ => vec![(m.src(), m.dst())],
-                }
-            }
+/// Animations of the pieces moved by a [`Move`], including the rook when castling.
+pub(crate) fn move_animations(m: Move) -> Vec<(Coord, Coord)> {
+    fn coord(f: File, r: Rank) -> Coord {
+        Coord::from_parts(f, r)
+    }
+
+    match m.kind() {
+        MoveKind::CastlingKingside => {
+            // King steps from the E to G file.
+            // Rook steps from the H to F file.
+            let rank = m.src().rank();
+            vec![
+                (coord(File::E, rank), coord(File::G, rank)),
+                (coord(File::H, rank), coord(File::F, rank)),
+            ]
+        }
+        MoveKind::CastlingQueenside => {
+            // King steps from the E to C file.
+            // Rook steps from the A to D file.
+            let rank = m.src().rank();
+            vec![
+                (coord(File::E, rank), coord(File::C, rank)),
+                (coord(File::A, rank), coord(File::D, rank)),
+            ]
         }
+        _ => vec![(m.src(), m.dst())],
     }
 }
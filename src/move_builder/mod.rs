@@ -1,4 +1,5 @@
 use owlchess::Move;
+use std::time::Duration;
 
 mod applicable_move;
 mod promotion;
@@ -10,7 +11,11 @@ pub enum MoveAction {
     None,
     /// A game mode action.
     /// Apply a [`Move`] to the [`HistoricalBoard`].
-    Apply(Move),
+    /// Moves built by the player might have to be confirmed by the host first.
+    Apply {
+        m: Move,
+        by_player: bool,
+    },
     /// A game mode action.
     /// Revert the last [`Move`] known to the [`HistoricalBoard`].
     Revert,
@@ -21,3 +26,14 @@ pub enum MoveAction {
     /// Set the _next_ move on the [`HistoricalBoard`], if any.
     StepForward,
 }
+
+/// How the moves of the player are handled by the board, provided as a context.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum PlayerMoves {
+    /// Moves are applied right away.
+    Applied,
+    /// Moves are applied once the host confirms them, or rolled back after the `timeout`.
+    Confirmed { timeout: Duration },
+    /// Moves are only proposed to the host controlling the position, see [`crate::ControlledPosition`].
+    Proposed,
+}
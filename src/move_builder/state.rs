@@ -1,13 +1,16 @@
-use crate::move_builder::applicable_move::ApplicableMove;
+use crate::move_builder::applicable_move::{move_animations, ApplicableMove};
 use crate::move_builder::promotion::Promotion;
 use crate::move_builder::MoveAction;
 use owlchess::board::PrettyStyle;
+use owlchess::movegen::legal;
 use owlchess::moves::{san, PromotePiece};
-use owlchess::{Board, Color, Coord, Move, Piece, Rank};
+use owlchess::{Board, Color, Coord, Move, MoveKind, Piece, Rank};
+use std::time::Duration;
 use tracing::{debug, warn};
 
 /// Builder for [Move] structured as a [MoveBuilder] machine:
 ///
+/// ```text
 ///                ┌──────────────┐
 ///                │     None     ◄──────────────────────────┐
 ///                └───────┬──────┘                          │
@@ -40,13 +43,23 @@ use tracing::{debug, warn};
 ///              └──────────┬────────┘                       │
 ///                         │                                │
 ///                         └────────── Animation ───────────►
+/// ```
 ///
+/// If moves of the player require a confirmation of the host, a built move stays [`MoveBuilder::Pending`]
+/// until it is either confirmed or rolled back as [`ApplicableMove::Rollback`].
 #[derive(Debug)]
 pub enum MoveBuilder {
     None,
     Src(Coord),
     Promotion(Promotion),
     ApplicableMove(ApplicableMove),
+    /// Move of the player waiting for the confirmation of the host until the `deadline`,
+    /// measured by the clock source of the board.
+    /// Its pieces are shown at their destinations.
+    Pending {
+        m: Move,
+        deadline: Duration,
+    },
 }
 
 // TODO Review these old functions
@@ -85,6 +98,7 @@ impl MoveBuilder {
             Self::Src(src) => Some(*src),
             Self::Promotion(manual) => Some(manual.src()),
             Self::ApplicableMove(m) => Some(m.src()),
+            Self::Pending { m, .. } => Some(m.src()),
             _ => None,
         }
     }
@@ -94,6 +108,7 @@ impl MoveBuilder {
         match self {
             Self::Promotion(manual) => Some(manual.dst()),
             Self::ApplicableMove(m) => Some(m.dst()),
+            Self::Pending { m, .. } => Some(m.dst()),
             _ => None,
         }
     }
@@ -111,17 +126,70 @@ impl MoveBuilder {
         )
     }
 
+    /// A square can be selected as a source if it holds a piece of the side to move.
+    pub(crate) fn is_selectable(coord: Coord, board: &Board) -> bool {
+        board
+            .get(coord)
+            .color()
+            .map(|c| c == board.side())
+            .unwrap_or_default()
+    }
+
+    /// Returns the selected source square if the destination is yet to be chosen.
+    pub(crate) fn selected(&self) -> Option<Coord> {
+        match self {
+            Self::Src(src) => Some(*src),
+            _ => None,
+        }
+    }
+
+    /// Computes hints for all legal moves of the selected piece:
+    /// destination squares with a flag whether the move is a capture.
+    pub(crate) fn move_hints(&self, board: &Board) -> Vec<(Coord, bool)> {
+        let Some(src) = self.selected() else {
+            return vec![];
+        };
+
+        let mut hints = legal::gen_all(board)
+            .iter()
+            .filter(|m| m.src() == src)
+            .map(|m| {
+                let is_capture =
+                    board.get(m.dst()).is_occupied() || m.kind() == MoveKind::Enpassant;
+                (m.dst(), is_capture)
+            })
+            .collect::<Vec<_>>();
+
+        // Promotions to different pieces share the destination.
+        hints.dedup();
+
+        hints
+    }
+
+    /// Selects a source square, replacing the previous selection.
+    /// Returns `false` if the square cannot be selected or a move is already being built further.
+    pub(crate) fn select(&mut self, coord: Coord, board: &Board) -> bool {
+        if matches!(self, Self::None | Self::Src(_)) && Self::is_selectable(coord, board) {
+            *self = Self::Src(coord);
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Cancels the selection of a source square.
+    pub(crate) fn cancel(&mut self) {
+        if let Self::Src(_) = self {
+            *self = Self::None;
+        }
+    }
+
     /// Puts a square into [MoveBuilder].
     pub(crate) fn put_square_coord(&mut self, coord: Coord, board: &Board) {
         *self = match self {
             // Start building a move by selecting a piece.
             Self::None => {
-                if board
-                    .get(coord)
-                    .color()
-                    .map(|c| c == board.side())
-                    .unwrap_or_default()
-                {
+                if Self::is_selectable(coord, board) {
                     // Selecting a piece of the right color to move.
                     Self::Src(coord)
                 } else {
@@ -178,11 +246,48 @@ impl MoveBuilder {
         san: &str,
         board: &Board,
     ) -> Result<(), san::ParseError> {
-        *self = Self::ApplicableMove(ApplicableMove::Automatic(Move::from_san(san, board)?));
+        self.apply_move(Move::from_san(san, board)?);
 
         Ok(())
     }
 
+    /// Plays a legal [`Move`] automatically, e.g., a reply of the opponent in a puzzle.
+    pub(crate) fn apply_move(&mut self, m: Move) {
+        *self = Self::ApplicableMove(ApplicableMove::Automatic(m));
+    }
+
+    /// Plays a premove of the player as if it were built manually.
+    pub(crate) fn premove(&mut self, m: Move) {
+        *self = Self::ApplicableMove(ApplicableMove::Manual(m));
+    }
+
+    /// Keeps a move of the player pending until the host confirms it or the `deadline` passes.
+    pub(crate) fn propose(&mut self, m: Move, deadline: Duration) {
+        *self = Self::Pending { m, deadline };
+    }
+
+    /// Returns the pending move with its deadline.
+    pub(crate) fn pending(&self) -> Option<(Move, Duration)> {
+        match self {
+            Self::Pending { m, deadline } => Some((*m, *deadline)),
+            _ => None,
+        }
+    }
+
+    /// Takes the pending move out to be applied.
+    pub(crate) fn confirm(&mut self) -> Option<Move> {
+        let (m, _) = self.pending()?;
+        *self = Self::None;
+        Some(m)
+    }
+
+    /// Rolls the pending move back with an animation.
+    pub(crate) fn reject(&mut self) -> Option<Move> {
+        let (m, _) = self.pending()?;
+        *self = Self::ApplicableMove(ApplicableMove::Rollback(m));
+        Some(m)
+    }
+
     /// [`MoveBuilder`] manages all animations, thus reverting the move also goes via the builder
     /// to produce a correct animation.
     pub(crate) fn revert_move(&mut self, m: Move) {
@@ -249,6 +354,7 @@ impl MoveBuilder {
         match self {
             Self::Promotion(promotion) => promotion.animations(),
             Self::ApplicableMove(applicable_move) => applicable_move.animations(),
+            Self::Pending { m, .. } => move_animations(*m),
             _ => vec![],
         }
     }
@@ -264,9 +370,15 @@ impl MoveBuilder {
             }
             Self::ApplicableMove(final_move) => {
                 let action = match final_move {
-                    ApplicableMove::Manual(m) | ApplicableMove::Automatic(m) => {
-                        MoveAction::Apply(*m)
-                    }
+                    ApplicableMove::Manual(m) => MoveAction::Apply {
+                        m: *m,
+                        by_player: true,
+                    },
+                    ApplicableMove::Automatic(m) => MoveAction::Apply {
+                        m: *m,
+                        by_player: false,
+                    },
+                    ApplicableMove::Rollback(_) => MoveAction::None,
                     ApplicableMove::Revert(_) => MoveAction::Revert,
                     ApplicableMove::Previous(_) => MoveAction::StepBack,
                     ApplicableMove::Next(_) => MoveAction::StepForward,
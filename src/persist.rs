@@ -0,0 +1,77 @@
+use crate::history::{BoardAction, BoardState, HistoricalBoard};
+use crate::Shape;
+use dioxus::prelude::*;
+use serde::{Deserialize, Serialize};
+use tracing::{debug, warn};
+use web_sys::Storage;
+
+/// Version of the saved game format, games saved in other versions are discarded.
+const VERSION: u32 = 1;
+
+/// Game saved in the local storage.
+#[derive(Serialize, Deserialize)]
+struct Saved {
+    version: u32,
+    state: BoardState,
+}
+
+/// Header of a saved game, which is readable in every version.
+#[derive(Deserialize)]
+struct Version {
+    version: u32,
+}
+
+/// Restores the game saved under the `key` together with the shapes drawn over the board.
+/// A corrupted or outdated game is discarded.
+pub(crate) fn load(
+    key: &str,
+    move_tx: Option<Coroutine<BoardAction>>,
+) -> Option<(HistoricalBoard, Vec<Shape>)> {
+    let saved = storage()?.get_item(key).ok()??;
+
+    let version = serde_json::from_str::<Version>(&saved)
+        .inspect_err(|err| warn!("Game saved under {key} is corrupted: {err}"))
+        .ok()?
+        .version;
+    if version != VERSION {
+        debug!("Game saved under {key} in version {version} is outdated");
+        return None;
+    }
+
+    serde_json::from_str::<Saved>(&saved)
+        .map_err(|err| err.to_string())
+        .and_then(|saved| {
+            HistoricalBoard::from_state(&saved.state, move_tx)
+                .map(|board| (board, saved.state.shapes().to_vec()))
+                .map_err(|err| err.to_string())
+        })
+        .inspect_err(|err| warn!("Game saved under {key} is corrupted: {err}"))
+        .ok()
+}
+
+/// Saves the game on the board in the context and the shapes drawn over it under the `key`
+/// after every change.
+pub(crate) fn use_persistence(key: Option<String>) {
+    let historical_board = use_context::<Signal<HistoricalBoard>>();
+    let shapes = use_context::<Signal<Vec<Shape>>>();
+
+    use_effect(move || {
+        let Some(key) = key.as_ref() else {
+            return;
+        };
+
+        let saved = Saved {
+            version: VERSION,
+            state: historical_board.read().to_state(&shapes.read()),
+        };
+        let saved = serde_json::to_string(&saved).expect("Board state is serializable");
+
+        if let Some(Err(err)) = storage().map(|storage| storage.set_item(key, &saved)) {
+            warn!("Game cannot be saved under {key}: {err:?}");
+        }
+    });
+}
+
+fn storage() -> Option<Storage> {
+    web_sys::window()?.local_storage().ok()?
+}
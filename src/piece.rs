@@ -1,14 +1,19 @@
+use crate::drag::{Drag, DragSource};
+use crate::editor::EditedPosition;
 use crate::history::HistoricalBoard;
-use crate::move_builder::MoveBuilder;
+use crate::move_builder::{MoveBuilder, PlayerMoves};
 use crate::pieces::compute_piece_img_src;
+use crate::puzzle::Puzzle;
 use crate::{finalize, PieceSet};
 use dioxus::prelude::*;
 use owlchess::{Color, Coord};
+use std::time::Duration;
 
 /// Component rendering pieces on [owlchess::Board].
 #[component]
 pub(crate) fn Piece(props: PieceProps) -> Element {
     let mut move_builder = use_context::<Signal<MoveBuilder>>();
+    let drag = use_context::<Signal<Option<Drag>>>();
 
     // If promotion is required,
     // do _not_ place pieces in src and dst.
@@ -21,28 +26,60 @@ pub(crate) fn Piece(props: PieceProps) -> Element {
     }
 
     let mut board = use_context::<Signal<HistoricalBoard>>();
+    let mut puzzle = use_context::<Signal<Option<Puzzle>>>();
+    let player_moves = use_context::<PlayerMoves>();
+    let editor = use_context::<Signal<Option<EditedPosition>>>();
 
-    let Some(img_src) = compute_piece_img_src(props.pieces_set, board.read().get(props.coord))
-    else {
+    // In the editor mode, the edited position is shown instead of the game.
+    let cell = match editor.read().as_ref() {
+        Some(position) => position.get(props.coord),
+        None => board.read().get(props.coord),
+    };
+
+    let Some(img_src) = compute_piece_img_src(&props.pieces_set, cell) else {
         return rsx! {};
     };
 
     // Animation.
+    // Without animations, the piece is displaced instantly, e.g., to show a pending move.
     let animation = move_builder
         .read()
         .animation_displacement(props.coord, props.color);
 
+    // Dragging.
+    let drag_offset = drag
+        .read()
+        .filter(|drag| drag.src == DragSource::Square(props.coord))
+        .map(|drag| drag.offset());
+
+    let transform = match (drag_offset, animation) {
+        (Some((x, y)), _) => Some(format!(
+            "translateX({x}px) translateY({y}px) scale(var(--piece-scale))"
+        )),
+        (None, Some((x, y))) => Some(format!(
+            "translateX({x}%) translateY({y}%) scale(var(--piece-scale))"
+        )),
+        (None, None) => None,
+    };
+
     let ontransitionend = move |_ev| {
-        finalize(&mut move_builder, &mut board);
+        finalize(&mut move_builder, &mut board, &mut puzzle, player_moves);
     };
 
     rsx! {
         img {
             src: img_src,
             class: "scaled",
-            z_index: if animation.is_some() { "10000" },
-            transition: if animation.is_some() { "transform 0.5s ease" },
-            transform: if let Some((x, y)) = animation { "translateX({x}%) translateY({y}%) scale(var(--piece-scale))" },
+            draggable: false,
+            z_index: if animation.is_some() || drag_offset.is_some() { "10000" },
+            // Dragged piece must not hide the squares under it from the pointer.
+            pointer_events: if drag_offset.is_some() { "none" },
+            transition: if animation.is_some() && drag_offset.is_none()
+                && !props.animation_duration.is_zero()
+            {
+                format!("transform {}ms ease", props.animation_duration.as_millis())
+            },
+            transform,
             ontransitionend,
         }
     }
@@ -53,4 +90,5 @@ pub(crate) struct PieceProps {
     coord: Coord,
     color: Color,
     pieces_set: PieceSet,
+    animation_duration: Duration,
 }
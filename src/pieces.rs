@@ -2,19 +2,80 @@ use dioxus::prelude::Asset;
 use funny::*;
 use owlchess::{Cell, Color, Piece};
 use standard::*;
+use std::fmt::Debug;
+use std::rc::Rc;
 
-#[derive(Copy, Clone, PartialEq, Debug)]
+#[derive(Clone, PartialEq, Debug)]
 pub enum PieceSet {
     Standard,
     Funny,
+    /// Pieces supplied by the application, see [`PieceTheme`].
+    Custom(CustomPieces),
 }
 
-pub(crate) fn compute_piece_img_src(pieces_set: PieceSet, cell: Cell) -> Option<Asset> {
+impl PieceSet {
+    /// Pieces drawn with the images of the `theme`.
+    pub fn custom(theme: impl PieceTheme + 'static) -> Self {
+        Self::Custom(CustomPieces(Rc::new(theme)))
+    }
+}
+
+/// Source of the piece images of a [`PieceSet::Custom`].
+///
+/// It is implemented for closures mapping a piece and its color to an image.
+pub trait PieceTheme {
+    fn image(&self, piece: Piece, color: Color) -> PieceImage;
+}
+
+impl<F: Fn(Piece, Color) -> PieceImage> PieceTheme for F {
+    fn image(&self, piece: Piece, color: Color) -> PieceImage {
+        self(piece, color)
+    }
+}
+
+/// Image of a piece.
+#[derive(Clone, PartialEq, Debug)]
+pub enum PieceImage {
+    /// URL of the image, e.g., of an asset or a data URL.
+    Url(String),
+    /// SVG document embedded into the page.
+    Svg(String),
+}
+
+impl PieceImage {
+    /// URL the image can be loaded from.
+    fn into_url(self) -> String {
+        match self {
+            Self::Url(url) => url,
+            Self::Svg(svg) => format!("data:image/svg+xml,{}", percent_encode(&svg)),
+        }
+    }
+}
+
+/// Shared [`PieceTheme`] of a [`PieceSet::Custom`].
+/// Handles are equal if they refer to the same theme.
+#[derive(Clone)]
+pub struct CustomPieces(Rc<dyn PieceTheme>);
+
+impl PartialEq for CustomPieces {
+    fn eq(&self, other: &Self) -> bool {
+        Rc::ptr_eq(&self.0, &other.0)
+    }
+}
+
+impl Debug for CustomPieces {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("CustomPieces").finish_non_exhaustive()
+    }
+}
+
+/// Computes the URL of the image of the piece occupying the `cell`, if any.
+pub(crate) fn compute_piece_img_src(pieces_set: &PieceSet, cell: Cell) -> Option<String> {
     if cell.is_occupied() {
         // Unwraps are safe because the cell is occupied.
         let piece = cell.piece().unwrap();
         let color = cell.color().unwrap();
-        Some(match (pieces_set, piece, color) {
+        let asset: Asset = match (pieces_set, piece, color) {
             // Pieces set 1.
             (PieceSet::Standard, Piece::Bishop, Color::White) => PIECE_1_B_WHITE,
             (PieceSet::Standard, Piece::King, Color::White) => PIECE_1_K_WHITE,
@@ -42,12 +103,47 @@ pub(crate) fn compute_piece_img_src(pieces_set: PieceSet, cell: Cell) -> Option<
             (PieceSet::Funny, Piece::Pawn, Color::Black) => PIECE_2_P_BLACK,
             (PieceSet::Funny, Piece::Queen, Color::Black) => PIECE_2_Q_BLACK,
             (PieceSet::Funny, Piece::Rook, Color::Black) => PIECE_2_R_BLACK,
-        })
+
+            // Pieces supplied by the application.
+            (PieceSet::Custom(CustomPieces(theme)), piece, color) => {
+                return Some(theme.image(piece, color).into_url());
+            }
+        };
+        Some(asset.to_string())
     } else {
         None
     }
 }
 
+/// Encodes the `text` to be embedded into a data URL.
+fn percent_encode(text: &str) -> String {
+    let mut encoded = String::with_capacity(text.len());
+
+    for byte in text.bytes() {
+        match byte {
+            b'A'..=b'Z'
+            | b'a'..=b'z'
+            | b'0'..=b'9'
+            | b'-'
+            | b'_'
+            | b'.'
+            | b'~'
+            | b' '
+            | b'/'
+            | b'='
+            | b':'
+            | b';'
+            | b','
+            | b'\''
+            | b'('
+            | b')' => encoded.push(char::from(byte)),
+            _ => encoded.push_str(&format!("%{byte:02X}")),
+        }
+    }
+
+    encoded
+}
+
 /// Piece set 1.
 mod standard {
     use dioxus::prelude::*;
@@ -0,0 +1,93 @@
+use owlchess::{Board, Color, Coord, Move, Piece, Rank};
+use std::collections::VecDeque;
+use tracing::debug;
+
+/// Moves queued by the player while the opponent is thinking.
+///
+/// Premoves are only squares: their legality is checked when it is the player's turn,
+/// and a pawn reaching the last rank is promoted to a queen.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub(crate) struct Premoves {
+    /// Source and destination squares of the queued moves, in the order they are played.
+    queue: VecDeque<(Coord, Coord)>,
+    /// Source square of a premove being built.
+    selected: Option<Coord>,
+}
+
+impl Premoves {
+    pub(crate) fn new() -> Self {
+        Self::default()
+    }
+
+    pub(crate) fn is_empty(&self) -> bool {
+        self.queue.is_empty() && self.selected.is_none()
+    }
+
+    pub(crate) fn selected(&self) -> Option<Coord> {
+        self.selected
+    }
+
+    /// Is the square the source or the destination of a queued premove?
+    pub(crate) fn is_queued(&self, coord: Coord) -> bool {
+        self.queue
+            .iter()
+            .any(|&(src, dst)| src == coord || dst == coord)
+    }
+
+    /// Puts a square into the premove being built.
+    /// A piece of the `player` is selected first, either on the `board`
+    /// or at the destination of a queued premove; the next different square completes the premove.
+    pub(crate) fn put_square_coord(&mut self, coord: Coord, board: &Board, player: Color) {
+        match self.selected.take() {
+            Some(src) if src == coord => {}
+            Some(src) => {
+                debug!("Premove {src}{coord} is queued");
+                self.queue.push_back((src, coord));
+            }
+            None if self.is_selectable(coord, board, player) => self.selected = Some(coord),
+            None => {}
+        }
+    }
+
+    /// A square can be selected if a piece of the `player` is going to stand there.
+    fn is_selectable(&self, coord: Coord, board: &Board, player: Color) -> bool {
+        let piece_moved_there = self.queue.iter().rev().find_map(|&(src, dst)| {
+            if dst == coord {
+                Some(true)
+            } else if src == coord {
+                Some(false)
+            } else {
+                None
+            }
+        });
+
+        piece_moved_there.unwrap_or_else(|| board.get(coord).color() == Some(player))
+    }
+
+    /// Cancels all premoves.
+    pub(crate) fn clear(&mut self) {
+        *self = Self::new();
+    }
+
+    /// Takes the first premove out of the queue if it is legal on the `board`.
+    /// Otherwise, the whole queue is dropped.
+    pub(crate) fn take_legal(&mut self, board: &Board) -> Option<Move> {
+        let (src, dst) = self.queue.pop_front()?;
+
+        let cell = board.get(src);
+        let promotion = match (cell.color(), cell.piece(), dst.rank()) {
+            (Some(Color::White), Some(Piece::Pawn), Rank::R8)
+            | (Some(Color::Black), Some(Piece::Pawn), Rank::R1) => "q",
+            _ => "",
+        };
+
+        match Move::from_uci_legal(&format!("{src}{dst}{promotion}"), board) {
+            Ok(m) => Some(m),
+            Err(err) => {
+                debug!("Premove {src}{dst} is dropped with the queue: {err}");
+                self.clear();
+                None
+            }
+        }
+    }
+}
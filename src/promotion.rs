@@ -1,6 +1,7 @@
 use crate::history::HistoricalBoard;
-use crate::move_builder::MoveBuilder;
+use crate::move_builder::{MoveBuilder, PlayerMoves};
 use crate::pieces::compute_piece_img_src;
+use crate::puzzle::Puzzle;
 use crate::{finalize, PieceSet};
 use dioxus::prelude::*;
 use owlchess::moves::PromotePiece;
@@ -71,7 +72,7 @@ pub(crate) fn Promotion(props: PromotionProperties) -> Element {
     rsx! {
         div { class: promotion_container_classes,
             for piece in pieces {
-                PromotePiece { color, piece, pieces_set: props.pieces_set }
+                PromotePiece { color, piece, pieces_set: props.pieces_set.clone() }
             }
         }
     }
@@ -88,10 +89,12 @@ pub struct PromotionProperties {
 fn PromotePiece(props: PromotePieceProps) -> Element {
     let mut board = use_context::<Signal<HistoricalBoard>>();
     let mut move_builder = use_context::<Signal<MoveBuilder>>();
+    let mut puzzle = use_context::<Signal<Option<Puzzle>>>();
+    let player_moves = use_context::<PlayerMoves>();
 
     let cell = Cell::from_parts(props.color, Piece::from(props.piece));
 
-    let src = compute_piece_img_src(props.pieces_set, cell)
+    let src = compute_piece_img_src(&props.pieces_set, cell)
         .unwrap_or_else(|| panic!("Cell {cell} must be occupied"));
 
     let onclick = move |_ev| {
@@ -100,7 +103,7 @@ fn PromotePiece(props: PromotePieceProps) -> Element {
             move_builder.write().promote(props.piece, &board);
         }
 
-        finalize(&mut move_builder, &mut board);
+        finalize(&mut move_builder, &mut board, &mut puzzle, player_moves);
     };
 
     rsx! {
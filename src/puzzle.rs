@@ -0,0 +1,105 @@
+use crate::history::HistoricalBoardError;
+use owlchess::{Board, Move};
+
+/// Puzzle: a position with the expected solution line.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct Puzzle {
+    /// Moves of the solution.
+    /// Moves of the player alternate with the replies of the opponent, starting with a move of the player.
+    line: Vec<Move>,
+    /// Number of the solution moves played so far.
+    progress: usize,
+    state: PuzzleState,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum PuzzleState {
+    Playing,
+    Solved,
+    Failed,
+}
+
+/// Judgement of a move played on the board while a [`Puzzle`] is loaded.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum Verdict {
+    /// The move is correct, the opponent replies with the given move.
+    Correct { reply: Move },
+    /// The move completes the solution.
+    Solved,
+    /// The move is not a part of the solution.
+    Wrong { expected: Move },
+    /// The move is not judged, e.g., it is the opponent's reply or the puzzle is over.
+    Accepted,
+}
+
+impl Puzzle {
+    /// Creates a puzzle starting in the `board` position.
+    /// Each move of the `solution` is encoded either in UCI or in SAN.
+    pub(crate) fn new(board: &Board, solution: &[String]) -> Result<Self, HistoricalBoardError> {
+        if solution.is_empty() {
+            return Err(HistoricalBoardError::EmptySolution);
+        }
+
+        let mut board = board.clone();
+        let mut line = vec![];
+
+        for m in solution {
+            let m = match Move::from_uci_legal(m, &board) {
+                Ok(m) => m,
+                Err(_) => Move::from_san(m, &board)?,
+            };
+
+            board = board.make_move(m)?;
+            line.push(m);
+        }
+
+        Ok(Self {
+            line,
+            progress: 0,
+            state: PuzzleState::Playing,
+        })
+    }
+
+    /// Is the puzzle still being solved?
+    pub(crate) fn is_playing(&self) -> bool {
+        self.state == PuzzleState::Playing
+    }
+
+    /// Is the player expected to move?
+    pub(crate) fn is_player_turn(&self) -> bool {
+        self.is_playing() && self.progress % 2 == 0
+    }
+
+    /// Judges the move `m` played in the `board` position and advances the puzzle.
+    /// Any move giving checkmate is correct, even if the solution continues differently.
+    pub(crate) fn check(&mut self, board: &Board, m: Move) -> Verdict {
+        if self.state != PuzzleState::Playing {
+            return Verdict::Accepted;
+        }
+
+        if !self.is_player_turn() {
+            self.progress += 1;
+            return Verdict::Accepted;
+        }
+
+        let expected = self.line[self.progress];
+        let is_mate = board
+            .make_move(m)
+            .is_ok_and(|board| board.is_check() && !board.has_legal_moves());
+
+        if m != expected && !is_mate {
+            self.state = PuzzleState::Failed;
+            return Verdict::Wrong { expected };
+        }
+
+        self.progress += 1;
+
+        match self.line.get(self.progress) {
+            Some(&reply) if !is_mate => Verdict::Correct { reply },
+            _ => {
+                self.state = PuzzleState::Solved;
+                Verdict::Solved
+            }
+        }
+    }
+}
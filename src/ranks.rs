@@ -19,16 +19,16 @@ pub(crate) fn Ranks(props: RanksProps) -> Element {
                         match props.color {
                             Color::White => {
                                 if rank.index() % 2 == 0 {
-                                    "var(--color-light)"
+                                    "var(--color-coordinate-dark, var(--color-light))"
                                 } else {
-                                    "var(--color-dark)"
+                                    "var(--color-coordinate-light, var(--color-dark))"
                                 }
                             }
                             Color::Black => {
                                 if rank.index() % 2 == 0 {
-                                    "var(--color-dark)"
+                                    "var(--color-coordinate-light, var(--color-dark))"
                                 } else {
-                                    "var(--color-light)"
+                                    "var(--color-coordinate-dark, var(--color-light))"
                                 }
                             }
                         },
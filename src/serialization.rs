@@ -0,0 +1,92 @@
+//! Serde helpers for the types of [`owlchess`], which do not implement serde traits themselves.
+
+/// Sides are encoded as `white` and `black`.
+pub(crate) mod side {
+    use owlchess::Color;
+    use serde::{de, Deserialize, Deserializer, Serializer};
+
+    pub(crate) fn serialize<S: Serializer>(side: &Color, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(side.as_long_str())
+    }
+
+    pub(crate) fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Color, D::Error> {
+        match String::deserialize(deserializer)?.as_str() {
+            "white" => Ok(Color::White),
+            "black" => Ok(Color::Black),
+            side => Err(de::Error::unknown_variant(side, &["white", "black"])),
+        }
+    }
+}
+
+/// Squares are encoded by their names, e.g., `e4`.
+pub(crate) mod coord {
+    use owlchess::Coord;
+    use serde::{de, Deserialize, Deserializer, Serializer};
+    use std::str::FromStr;
+
+    pub(crate) fn serialize<S: Serializer>(
+        coord: &Coord,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(coord)
+    }
+
+    pub(crate) fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Coord, D::Error> {
+        let name = String::deserialize(deserializer)?;
+        Coord::from_str(&name).map_err(de::Error::custom)
+    }
+}
+
+/// Optional sides are encoded as `white`, `black` or nothing.
+pub(crate) mod optional_side {
+    use owlchess::Color;
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    #[derive(Serialize, Deserialize)]
+    struct Side(#[serde(with = "super::side")] Color);
+
+    pub(crate) fn serialize<S: Serializer>(
+        side: &Option<Color>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        side.map(Side).serialize(serializer)
+    }
+
+    pub(crate) fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Option<Color>, D::Error> {
+        Ok(Option::<Side>::deserialize(deserializer)?.map(|Side(side)| side))
+    }
+}
+
+/// Game results are encoded as in PGN, i.e., `1-0`, `0-1`, `1/2-1/2` or `*`.
+pub(crate) mod status {
+    use owlchess::GameStatus;
+    use serde::{de, Deserialize, Deserializer, Serializer};
+
+    pub(crate) fn serialize<S: Serializer>(
+        status: &GameStatus,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(status)
+    }
+
+    pub(crate) fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<GameStatus, D::Error> {
+        match String::deserialize(deserializer)?.as_str() {
+            "1-0" => Ok(GameStatus::White),
+            "0-1" => Ok(GameStatus::Black),
+            "1/2-1/2" => Ok(GameStatus::Draw),
+            "*" => Ok(GameStatus::Running),
+            status => Err(de::Error::unknown_variant(
+                status,
+                &["1-0", "0-1", "1/2-1/2", "*"],
+            )),
+        }
+    }
+}
@@ -0,0 +1,249 @@
+use crate::history::{BoardAction, HistoricalBoard};
+use dioxus::html::input_data::keyboard_types::Modifiers;
+use dioxus::prelude::*;
+use owlchess::{Color, Coord};
+
+/// Annotation drawn over the board.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum Shape {
+    Arrow {
+        #[cfg_attr(feature = "serde", serde(with = "crate::serialization::coord"))]
+        src: Coord,
+        #[cfg_attr(feature = "serde", serde(with = "crate::serialization::coord"))]
+        dst: Coord,
+        color: ShapeColor,
+    },
+    Circle {
+        #[cfg_attr(feature = "serde", serde(with = "crate::serialization::coord"))]
+        square: Coord,
+        color: ShapeColor,
+    },
+}
+
+impl Shape {
+    /// Shape drawn from the `src` square to the `dst` one:
+    /// a circle if both are the same square, otherwise an arrow.
+    pub fn between(src: Coord, dst: Coord, color: ShapeColor) -> Self {
+        if src == dst {
+            Self::Circle { square: src, color }
+        } else {
+            Self::Arrow { src, dst, color }
+        }
+    }
+
+    /// Squares the shape is drawn between.
+    fn squares(&self) -> (Coord, Coord) {
+        match *self {
+            Self::Arrow { src, dst, .. } => (src, dst),
+            Self::Circle { square, .. } => (square, square),
+        }
+    }
+
+    fn color(&self) -> ShapeColor {
+        match *self {
+            Self::Arrow { color, .. } | Self::Circle { color, .. } => color,
+        }
+    }
+}
+
+/// Color of a [`Shape`].
+/// The player chooses it with modifier keys held while drawing.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum ShapeColor {
+    /// No modifier keys.
+    Green,
+    /// Shift or Ctrl.
+    Red,
+    /// Alt or Meta.
+    Blue,
+    /// Both Shift or Ctrl and Alt or Meta.
+    Yellow,
+}
+
+impl ShapeColor {
+    pub(crate) fn from_modifiers(modifiers: Modifiers) -> Self {
+        let is_red = modifiers.intersects(Modifiers::SHIFT | Modifiers::CONTROL);
+        let is_blue = modifiers.intersects(Modifiers::ALT | Modifiers::META);
+
+        match (is_red, is_blue) {
+            (false, false) => Self::Green,
+            (true, false) => Self::Red,
+            (false, true) => Self::Blue,
+            (true, true) => Self::Yellow,
+        }
+    }
+
+    fn class(&self) -> &'static str {
+        match self {
+            Self::Green => "green",
+            Self::Red => "red",
+            Self::Blue => "blue",
+            Self::Yellow => "yellow",
+        }
+    }
+}
+
+/// Shape being drawn with the secondary pointer button.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) struct Drawing {
+    src: Coord,
+    /// Square under the pointer.
+    dst: Coord,
+    color: ShapeColor,
+}
+
+impl Drawing {
+    pub(crate) fn new(src: Coord, color: ShapeColor) -> Self {
+        Self {
+            src,
+            dst: src,
+            color,
+        }
+    }
+
+    pub(crate) fn dst(&self) -> Coord {
+        self.dst
+    }
+
+    pub(crate) fn move_to(&mut self, dst: Coord) {
+        self.dst = dst;
+    }
+
+    pub(crate) fn shape(&self) -> Shape {
+        Shape::between(self.src, self.dst, self.color)
+    }
+}
+
+/// Adds the `shape` to the `shapes`, or removes it if it is already drawn.
+/// A shape drawn between the same squares in another color replaces the existing one.
+pub(crate) fn toggle(shapes: &mut Vec<Shape>, shape: Shape) {
+    let existing = shapes.iter().position(|s| s.squares() == shape.squares());
+
+    match existing {
+        Some(i) if shapes[i] == shape => {
+            shapes.remove(i);
+        }
+        Some(i) => shapes[i] = shape,
+        None => shapes.push(shape),
+    }
+}
+
+/// Reports the shapes drawn over the board by the player.
+pub(crate) fn report_shapes(historical_board: &HistoricalBoard, shapes: &[Shape]) {
+    if let Some(tx) = historical_board.move_tx.as_ref() {
+        tx.send(BoardAction::Shapes(shapes.to_vec()));
+    }
+}
+
+/// Component rendering the shapes drawn over the board as an SVG overlay.
+#[component]
+pub(crate) fn Shapes(props: ShapesProps) -> Element {
+    let shapes = use_context::<Signal<Vec<Shape>>>();
+    let drawing = use_context::<Signal<Option<Drawing>>>();
+
+    // Centre of the square in the coordinates of the overlay, where every square is 1x1.
+    let centre = |coord: Coord| {
+        let (column, row) = match props.color {
+            Color::White => (coord.file().index(), coord.rank().index()),
+            Color::Black => (7 - coord.file().index(), 7 - coord.rank().index()),
+        };
+
+        (column as f64 + 0.5, row as f64 + 0.5)
+    };
+
+    let shapes = shapes
+        .read()
+        .iter()
+        .copied()
+        .chain(drawing.read().as_ref().map(Drawing::shape))
+        .collect::<Vec<_>>();
+
+    rsx! {
+        svg { class: "shapes", view_box: "0 0 8 8",
+            for shape in shapes {
+                g { class: "shape {shape.color().class()}",
+                    match shape {
+                        Shape::Arrow { src, dst, .. } => {
+                            let arrow = Arrow::new(centre(src), centre(dst));
+                            rsx! {
+                                line {
+                                    x1: arrow.start.0,
+                                    y1: arrow.start.1,
+                                    x2: arrow.shaft_end.0,
+                                    y2: arrow.shaft_end.1,
+                                    stroke_width: 0.15,
+                                    stroke_linecap: "round",
+                                }
+                                polygon { stroke: "none", points: arrow.head }
+                            }
+                        }
+                        Shape::Circle { square, .. } => {
+                            let (cx, cy) = centre(square);
+                            rsx! {
+                                circle {
+                                    cx,
+                                    cy,
+                                    r: 0.45,
+                                    fill: "none",
+                                    stroke_width: 0.07,
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Geometry of an arrow between the centres of two squares.
+struct Arrow {
+    start: (f64, f64),
+    /// End of the shaft, where the head begins.
+    shaft_end: (f64, f64),
+    /// Points of the triangular head in the SVG format.
+    head: String,
+}
+
+impl Arrow {
+    const HEAD_LENGTH: f64 = 0.45;
+    const HEAD_WIDTH: f64 = 0.45;
+
+    fn new(start: (f64, f64), end: (f64, f64)) -> Self {
+        let (dx, dy) = (end.0 - start.0, end.1 - start.1);
+        let length = dx.hypot(dy);
+        // Unit vectors along and across the arrow.
+        let (ux, uy) = (dx / length, dy / length);
+        let (nx, ny) = (-uy, ux);
+
+        let shaft_end = (
+            end.0 - ux * Self::HEAD_LENGTH,
+            end.1 - uy * Self::HEAD_LENGTH,
+        );
+        let half_width = Self::HEAD_WIDTH / 2.0;
+
+        let head = [
+            end,
+            (shaft_end.0 + nx * half_width, shaft_end.1 + ny * half_width),
+            (shaft_end.0 - nx * half_width, shaft_end.1 - ny * half_width),
+        ]
+        .map(|(x, y)| format!("{x},{y}"))
+        .join(" ");
+
+        Self {
+            start,
+            shaft_end,
+            head,
+        }
+    }
+}
+
+#[derive(Props, Debug, PartialEq, Clone)]
+pub(crate) struct ShapesProps {
+    /// [`Color`] the player plays for.
+    color: Color,
+}
@@ -1,43 +1,232 @@
+use crate::drag::{Drag, DragSource};
+use crate::editor::{edit, EditedPosition};
 use crate::history::HistoricalBoard;
+use crate::keyboard::{square_label, KeyboardFocus};
 use crate::move_builder::MoveBuilder;
 use crate::piece::Piece;
+use crate::premove::Premoves;
+use crate::shapes::{report_shapes, Drawing, Shape, ShapeColor};
 use crate::PieceSet;
 use dioxus::core_macro::{component, Props};
 use dioxus::dioxus_core::Element;
+use dioxus::html::input_data::MouseButton;
 use dioxus::prelude::*;
 use owlchess::{Color, Coord};
+use std::rc::Rc;
+use std::time::Duration;
 
 /// A component rendering a square, potentially with a [`Piece`] inside.
 #[component]
 pub(crate) fn Square(props: SquareProps) -> Element {
     let board = use_context::<Signal<HistoricalBoard>>();
     let mut move_builder = use_context::<Signal<MoveBuilder>>();
+    let mut drag = use_context::<Signal<Option<Drag>>>();
+    let mut editor = use_context::<Signal<Option<EditedPosition>>>();
+    let mut premoves = use_context::<Signal<Premoves>>();
+    let mut shapes = use_context::<Signal<Vec<Shape>>>();
+    let mut drawing = use_context::<Signal<Option<Drawing>>>();
+    let mut focus = use_context::<Signal<KeyboardFocus>>();
+    let mut element = use_signal(|| None::<Rc<MountedData>>);
+
+    // Square focused with the keyboard receives the focus of the document.
+    let is_focused = props.is_focused;
+    use_effect(use_reactive!(|is_focused| {
+        if !is_focused {
+            return;
+        }
+
+        if let Some(element) = element.peek().clone() {
+            spawn(async move {
+                let _ = element.set_focus(true).await;
+            });
+        }
+    }));
+
+    // In the editor mode, the edited position is described instead of the game.
+    let label = {
+        let cell = match editor.read().as_ref() {
+            Some(position) => position.get(props.coord),
+            None => board.read().get(props.coord),
+        };
+        square_label(props.coord, cell)
+    };
 
     // Highlight a selected square if no animation is in progress.
     let is_selected = move_builder.read().find_animation(props.coord).is_none()
         && matches!(move_builder.read().src(), Some(src) if src == props.coord);
 
+    let mut classes = vec![];
+
+    if props.is_last_move {
+        classes.push("last-move");
+    }
+
+    if props.is_check {
+        classes.push("check");
+    }
+
+    if is_selected {
+        classes.push("move-source");
+    }
+
+    if premoves.read().selected() == Some(props.coord) {
+        classes.push("premove-source");
+    } else if premoves.read().is_queued(props.coord) {
+        classes.push("premove");
+    }
+
+    if move_builder
+        .read()
+        .pending()
+        .is_some_and(|(m, _)| m.src() == props.coord || m.dst() == props.coord)
+    {
+        classes.push("pending-move");
+    }
+
+    match props.move_hint {
+        Some(MoveHint::Move) => classes.push("move-hint"),
+        Some(MoveHint::Capture) => classes.push("capture-hint"),
+        None => {}
+    }
+
     rsx! {
         div {
             id: format!("{}", props.coord),
-            class: if is_selected { "move-source" },
+            class: classes.join(" "),
+            role: "gridcell",
+            aria_label: label,
+            aria_selected: is_selected,
+            // Only one square is reachable with the Tab key, the others are reached with the arrow keys.
+            tabindex: if props.is_tab_stop { 0 } else { -1 },
+            onmounted: move |ev| element.set(Some(ev.data())),
+            onfocus: move |_ev| {
+                if focus.peek().0 != Some(props.coord) {
+                    focus.set(KeyboardFocus(Some(props.coord)));
+                }
+            },
+            onkeydown: move |ev| {
+                // Enter and Space act as a click on the square.
+                if ev.key() != Key::Enter && ev.key() != Key::Character(" ".to_string()) {
+                    return;
+                }
+                ev.prevent_default();
+
+                if props.is_premoving {
+                    premoves.write().put_square_coord(props.coord, &board.read(), props.color);
+                } else if props.is_interactive && !props.is_editing {
+                    move_builder.write().put_square_coord(props.coord, &board.read());
+                }
+            },
             onclick: move |_ev| {
-                if props.is_interactive {
+                // With dragging enabled, clicks are handled as pointer presses.
+                if props.is_interactive && !props.is_draggable {
                     move_builder.write().put_square_coord(props.coord, &board.read());
                 }
             },
+            oncontextmenu: move |ev| {
+                // Right click removes a piece in the editor mode, otherwise it draws shapes.
+                ev.prevent_default();
+
+                if props.is_editing {
+                    edit(&mut editor, &board, |position| position.remove(props.coord));
+                }
+            },
+            onpointerdown: move |ev| {
+                if props.is_editing {
+                    let is_occupied = editor
+                        .read()
+                        .as_ref()
+                        .is_some_and(|position| position.get(props.coord).is_occupied());
+
+                    if is_occupied && ev.trigger_button() == Some(MouseButton::Primary) {
+                        drag.set(
+                            Some(Drag::new(DragSource::Square(props.coord), ev.client_coordinates(), false)),
+                        );
+                    }
+                    return;
+                }
+
+                // Shapes are drawn with the secondary button and erased with the primary one.
+                // The secondary button cancels premoves instead, if there are any.
+                match ev.trigger_button() {
+                    Some(MouseButton::Secondary) if !premoves.peek().is_empty() => {
+                        premoves.write().clear();
+                        return;
+                    }
+                    Some(MouseButton::Secondary) => {
+                        let color = ShapeColor::from_modifiers(ev.modifiers());
+                        drawing.set(Some(Drawing::new(props.coord, color)));
+                        return;
+                    }
+                    Some(MouseButton::Primary) if !shapes.peek().is_empty() => {
+                        shapes.write().clear();
+                        report_shapes(&board.peek(), &shapes.peek());
+                    }
+                    _ => {}
+                }
+
+                if props.is_premoving && ev.trigger_button() == Some(MouseButton::Primary) {
+                    premoves.write().put_square_coord(props.coord, &board.read(), props.color);
+                    return;
+                }
+
+                if !props.is_interactive || !props.is_draggable
+                    || ev.trigger_button() != Some(MouseButton::Primary)
+                {
+                    return;
+                }
+
+                let board = board.read();
+                let position = ev.client_coordinates();
+                let selected = move_builder.read().selected();
+
+                if selected == Some(props.coord) {
+                    // Pressing the selected piece again either drags it or deselects it.
+                    drag.set(Some(Drag::new(DragSource::Square(props.coord), position, true)));
+                } else if move_builder.write().select(props.coord, &board) {
+                    drag.set(Some(Drag::new(DragSource::Square(props.coord), position, false)));
+                } else if selected.is_some() {
+                    // Click-click: the destination square is chosen.
+                    move_builder.write().put_square_coord(props.coord, &board);
+                }
+            },
             Piece {
                 coord: props.coord,
                 color: props.color,
-                pieces_set: props.pieces_set,
+                pieces_set: props.pieces_set.clone(),
+                animation_duration: props.animation_duration,
             }
         }
     }
 }
 
+/// Hint that the selected piece can legally move to a square.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum MoveHint {
+    /// The square is empty.
+    Move,
+    /// The move captures a piece.
+    Capture,
+}
+
 #[derive(Props, Debug, PartialEq, Clone)]
 pub(crate) struct SquareProps {
     is_interactive: bool,
+    /// Can the player queue premoves while the opponent is thinking?
+    is_premoving: bool,
+    /// Is the square focused with the keyboard?
+    is_focused: bool,
+    /// Is the square reachable with the Tab key?
+    is_tab_stop: bool,
+    is_draggable: bool,
+    animation_duration: Duration,
+    /// Is the board in the editor mode?
+    is_editing: bool,
+    move_hint: Option<MoveHint>,
+    /// Is the square the source or the destination of the move leading to the current position?
+    is_last_move: bool,
+    /// Is the square occupied by the king in check?
+    is_check: bool,
     coord: Coord,
     color: Color,
     pieces_set: PieceSet,
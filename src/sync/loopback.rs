@@ -0,0 +1,42 @@
+use crate::transport::LineTransport;
+use std::cell::RefCell;
+use std::collections::VecDeque;
+use std::io;
+use std::rc::Rc;
+
+/// [`LineTransport`] connecting two boards of the same application in memory, e.g., in tests.
+#[derive(Debug)]
+pub struct LoopbackTransport {
+    outgoing: Rc<RefCell<VecDeque<String>>>,
+    incoming: Rc<RefCell<VecDeque<String>>>,
+}
+
+impl LoopbackTransport {
+    /// Creates two connected ends: messages sent to one of them are received by the other.
+    pub fn pair() -> (Self, Self) {
+        let there = Rc::new(RefCell::new(VecDeque::new()));
+        let back = Rc::new(RefCell::new(VecDeque::new()));
+
+        (
+            Self {
+                outgoing: there.clone(),
+                incoming: back.clone(),
+            },
+            Self {
+                outgoing: back,
+                incoming: there,
+            },
+        )
+    }
+}
+
+impl LineTransport for LoopbackTransport {
+    fn send(&mut self, message: &str) -> io::Result<()> {
+        self.outgoing.borrow_mut().push_back(message.to_string());
+        Ok(())
+    }
+
+    fn try_receive(&mut self) -> Option<String> {
+        self.incoming.borrow_mut().pop_front()
+    }
+}
@@ -0,0 +1,13 @@
+mod loopback;
+mod protocol;
+#[allow(clippy::module_inception)]
+mod sync;
+#[cfg(feature = "web")]
+mod websocket;
+
+pub use loopback::LoopbackTransport;
+pub use protocol::SyncMessage;
+pub(crate) use sync::use_board_sync;
+pub use sync::BoardSync;
+#[cfg(feature = "web")]
+pub use websocket::WebSocketTransport;
@@ -0,0 +1,40 @@
+use crate::Color;
+use serde::{Deserialize, Serialize};
+
+/// Message exchanged by the peers sharing a board, encoded in JSON.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum SyncMessage {
+    /// Move in the UCI notation played at the `ply`, see [`crate::SanMove::ply`].
+    Move {
+        ply: usize,
+        uci: String,
+    },
+    /// Complete game: the starting position in FEN notation and the moves in the UCI notation.
+    /// It replaces the game of the peer, e.g., after a takeback or a new game.
+    Resync {
+        fen: String,
+        moves: Vec<String>,
+    },
+    /// Request of a [`SyncMessage::Resync`], e.g., after a move has been missed.
+    RequestResync,
+    Resign {
+        #[serde(with = "crate::serialization::side")]
+        side: Color,
+    },
+    OfferDraw {
+        #[serde(with = "crate::serialization::side")]
+        side: Color,
+    },
+    AcceptDraw,
+}
+
+impl SyncMessage {
+    pub fn encode(&self) -> String {
+        serde_json::to_string(self).expect("Messages are serializable")
+    }
+
+    pub fn decode(message: &str) -> Result<Self, serde_json::Error> {
+        serde_json::from_str(message)
+    }
+}
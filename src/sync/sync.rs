@@ -0,0 +1,531 @@
+use crate::chessboard::chessboard::follow_line;
+use crate::chessboard::ply;
+use crate::history::{HistoricalBoard, Termination};
+use crate::move_builder::MoveBuilder;
+use crate::premove::Premoves;
+use crate::puzzle::Puzzle;
+use crate::sync::SyncMessage;
+use crate::transport::{poll_periodically, LineTransport};
+use dioxus::prelude::*;
+use owlchess::{Board, Color, GameStatus, Move};
+use std::cell::RefCell;
+use std::fmt::Debug;
+use std::rc::Rc;
+use std::str::FromStr;
+use tracing::{debug, warn};
+
+/// Shares the game on a [`crate::Chessboard`] with a peer over a [`LineTransport`].
+///
+/// Messages are encoded [`SyncMessage`]s, one per line.
+/// Moves, takebacks, new games, resignations and draw offers made on either board reach the other one.
+/// If both peers move at the same ply, both of them keep the move with the smaller UCI notation.
+/// The game on the board is assumed to be shared when the board is created,
+/// otherwise one of the peers has to request a [`SyncMessage::Resync`].
+/// Clones refer to the same connection, handles are equal if they do.
+#[derive(Clone)]
+pub struct BoardSync(Rc<RefCell<SyncState>>);
+
+struct SyncState {
+    transport: Box<dyn LineTransport>,
+    /// Game shared with the peer: the starting position and the moves played from it.
+    shared: Option<(Board, Vec<Move>)>,
+    /// Draw offer shared with the peer.
+    draw_offer: Option<Color>,
+    /// End of the game by the players shared with the peer.
+    agreement: Option<(GameStatus, Termination)>,
+}
+
+impl BoardSync {
+    /// Connects to the peer reachable via the `transport`.
+    pub fn new(transport: impl LineTransport + 'static) -> Self {
+        Self(Rc::new(RefCell::new(SyncState {
+            transport: Box::new(transport),
+            shared: None,
+            draw_offer: None,
+            agreement: None,
+        })))
+    }
+
+    /// Sends the changes of the game on the board, which are not shared yet, to the peer.
+    pub(crate) fn publish(&self, historical_board: &HistoricalBoard) {
+        let mut state = self.0.borrow_mut();
+        let start = historical_board.starting_board();
+        let line = historical_board.line();
+
+        // Number of moves the peer knows of, if the game on the board continues the shared one.
+        let known = match state.shared.as_ref() {
+            None => Some(line.len()),
+            Some((shared_start, shared_line))
+                if shared_start == start && line.starts_with(shared_line) =>
+            {
+                Some(shared_line.len())
+            }
+            Some(_) => None,
+        };
+
+        match known {
+            Some(known) => {
+                for (index, m) in line.iter().enumerate().skip(known) {
+                    state.send(&SyncMessage::Move {
+                        ply: ply(start) + index,
+                        uci: m.to_string(),
+                    });
+                }
+            }
+            None => state.send(&resync(start, &line)),
+        }
+        state.shared = Some((start.clone(), line));
+
+        let draw_offer = historical_board.draw_offer();
+        if draw_offer != state.draw_offer {
+            if let Some(side) = draw_offer {
+                state.send(&SyncMessage::OfferDraw { side });
+            }
+            state.draw_offer = draw_offer;
+        }
+
+        let agreement = historical_board.agreement();
+        if agreement != state.agreement {
+            match agreement {
+                Some((GameStatus::White, Termination::Resignation)) => {
+                    state.send(&SyncMessage::Resign { side: Color::Black });
+                }
+                Some((GameStatus::Black, Termination::Resignation)) => {
+                    state.send(&SyncMessage::Resign { side: Color::White });
+                }
+                Some((_, Termination::DrawAgreement)) => state.send(&SyncMessage::AcceptDraw),
+                _ => {}
+            }
+            state.agreement = agreement;
+        }
+    }
+
+    /// Takes the messages of the peer received since the last poll.
+    pub(crate) fn poll(&self) -> Vec<SyncMessage> {
+        let mut state = self.0.borrow_mut();
+        let mut messages = vec![];
+
+        while let Some(message) = state.transport.try_receive() {
+            match SyncMessage::decode(&message) {
+                Ok(message) => messages.push(message),
+                Err(err) => warn!("Message `{message}` of the peer cannot be decoded: {err}"),
+            }
+        }
+
+        messages
+    }
+
+    /// Applies a message of the peer to the board.
+    /// The shared state is updated first, so that the change is not sent back.
+    pub(crate) fn receive(
+        &self,
+        message: SyncMessage,
+        historical_board: &mut Signal<HistoricalBoard>,
+        move_builder: &mut Signal<MoveBuilder>,
+        puzzle: &mut Signal<Option<Puzzle>>,
+        premoves: &mut Signal<Premoves>,
+    ) {
+        match message {
+            SyncMessage::Move { ply, uci } => {
+                let accepted = self.0.borrow_mut().accept_move(ply, &uci);
+
+                if let Some((start, line)) = accepted {
+                    // A move losing to the move of the peer is taken back,
+                    // so that the game, its clocks and variations go on.
+                    if replaces_last_move(&historical_board.peek(), &start, &line) {
+                        historical_board.write().revert_last_move();
+                    }
+
+                    follow_line(
+                        start,
+                        &line,
+                        historical_board,
+                        move_builder,
+                        puzzle,
+                        premoves,
+                    );
+                }
+            }
+            SyncMessage::Resync { fen, moves } => {
+                let Some((start, line)) = parse_game(&fen, &moves) else {
+                    return;
+                };
+
+                self.0.borrow_mut().shared = Some((start.clone(), line.clone()));
+                follow_line(
+                    start,
+                    &line,
+                    historical_board,
+                    move_builder,
+                    puzzle,
+                    premoves,
+                );
+            }
+            SyncMessage::RequestResync => {
+                let mut state = self.0.borrow_mut();
+
+                if let Some((start, line)) = state.shared.clone() {
+                    state.send(&resync(&start, &line));
+                }
+            }
+            SyncMessage::Resign { side } => {
+                historical_board.write().resign(side);
+                self.0.borrow_mut().agreement = historical_board.peek().agreement();
+            }
+            SyncMessage::OfferDraw { side } => {
+                historical_board.write().offer_draw(side);
+                self.0.borrow_mut().draw_offer = historical_board.peek().draw_offer();
+            }
+            SyncMessage::AcceptDraw => {
+                if let Err(err) = historical_board.write().accept_draw() {
+                    warn!("Draw accepted by the peer cannot be agreed: {err}");
+                }
+
+                let mut state = self.0.borrow_mut();
+                state.agreement = historical_board.peek().agreement();
+                state.draw_offer = historical_board.peek().draw_offer();
+            }
+        }
+    }
+}
+
+impl SyncState {
+    fn send(&mut self, message: &SyncMessage) {
+        debug!("Sending {message:?} to the peer");
+
+        if let Err(err) = self.transport.send(&message.encode()) {
+            warn!("Message {message:?} cannot be sent to the peer: {err}");
+        }
+    }
+
+    /// Adds the move of the peer played at the `ply` to the shared game.
+    /// Returns the updated game, unless the move is dropped.
+    fn accept_move(&mut self, ply: usize, uci: &str) -> Option<(Board, Vec<Move>)> {
+        let (start, mut line) = self.shared.clone()?;
+
+        let Some(index) = ply
+            .checked_sub(self::ply(&start))
+            .filter(|&index| index <= line.len())
+        else {
+            warn!("Move {uci} of the peer at ply {ply} does not follow the shared game");
+            self.send(&SyncMessage::RequestResync);
+            return None;
+        };
+
+        // Both peers have moved at the same ply, the smaller UCI notation wins on both sides.
+        if let Some(played) = line.get(index) {
+            if played.to_string().as_str() <= uci {
+                debug!("Move {uci} of the peer loses to {played} at ply {ply}");
+                return None;
+            }
+
+            debug!("Move {uci} of the peer replaces {played} at ply {ply}");
+            line.truncate(index);
+        }
+
+        let board = replay(&start, &line);
+        match Move::from_uci_legal(uci, &board) {
+            Ok(m) => line.push(m),
+            Err(err) => {
+                warn!("Move {uci} of the peer is not legal: {err}");
+                self.send(&SyncMessage::RequestResync);
+                return None;
+            }
+        }
+
+        self.shared = Some((start.clone(), line.clone()));
+        Some((start, line))
+    }
+}
+
+/// Position reached by the `line` of legal moves played from the `start` position.
+fn replay(start: &Board, line: &[Move]) -> Board {
+    line.iter().fold(start.clone(), |board, m| {
+        board.make_move(*m).expect("Shared moves are legal")
+    })
+}
+
+/// Does the `line` played from the `start` position differ from the game on the board
+/// only in the last move?
+fn replaces_last_move(historical_board: &HistoricalBoard, start: &Board, line: &[Move]) -> bool {
+    let played = historical_board.line();
+
+    historical_board.starting_board() == start
+        && played.len() == line.len()
+        && played.split_last().zip(line.split_last()).is_some_and(
+            |((played, preceding), (m, expected))| played != m && preceding == expected,
+        )
+}
+
+fn resync(start: &Board, line: &[Move]) -> SyncMessage {
+    SyncMessage::Resync {
+        fen: start.as_fen(),
+        moves: line.iter().map(Move::to_string).collect(),
+    }
+}
+
+/// Parses the game of a [`SyncMessage::Resync`].
+fn parse_game(fen: &str, moves: &[String]) -> Option<(Board, Vec<Move>)> {
+    let start = Board::from_str(fen)
+        .inspect_err(|err| warn!("Position {fen} of the peer is not valid: {err}"))
+        .ok()?;
+
+    let mut board = start.clone();
+    let mut line = vec![];
+
+    for uci in moves {
+        let m = Move::from_uci_legal(uci, &board)
+            .inspect_err(|err| warn!("Move {uci} of the peer is not legal: {err}"))
+            .ok()?;
+
+        board = board.make_move(m).expect("Move is legal");
+        line.push(m);
+    }
+
+    Some((start, line))
+}
+
+impl PartialEq for BoardSync {
+    fn eq(&self, other: &Self) -> bool {
+        Rc::ptr_eq(&self.0, &other.0)
+    }
+}
+
+impl Debug for BoardSync {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let state = self.0.borrow();
+
+        f.debug_struct("BoardSync")
+            .field(
+                "shared",
+                &state
+                    .shared
+                    .as_ref()
+                    .map(|(start, line)| resync(start, line)),
+            )
+            .field("draw_offer", &state.draw_offer)
+            .field("agreement", &state.agreement)
+            .finish()
+    }
+}
+
+/// Keeps the game on the board in the context shared with the peer connected by the `sync`.
+pub(crate) fn use_board_sync(sync: Option<BoardSync>) {
+    let mut historical_board = use_context::<Signal<HistoricalBoard>>();
+    let mut move_builder = use_context::<Signal<MoveBuilder>>();
+    let mut puzzle = use_context::<Signal<Option<Puzzle>>>();
+    let mut premoves = use_context::<Signal<Premoves>>();
+
+    let publishing = sync.clone();
+    use_effect(move || {
+        if let Some(sync) = publishing.as_ref() {
+            sync.publish(&historical_board.read());
+        }
+    });
+
+    use_future(move || {
+        let sync = sync.clone();
+
+        async move {
+            let Some(sync) = sync else {
+                return;
+            };
+
+            poll_periodically(|| {
+                for message in sync.poll() {
+                    debug!("Peer sends {message:?}");
+                    sync.receive(
+                        message,
+                        &mut historical_board,
+                        &mut move_builder,
+                        &mut puzzle,
+                        &mut premoves,
+                    );
+                }
+            })
+            .await;
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::clock::{ManualClock, TimeControl};
+    use crate::sync::LoopbackTransport;
+    use std::time::Duration;
+
+    /// Board of one of the peers together with the context it shares with the component.
+    struct Peer {
+        sync: BoardSync,
+        historical_board: Signal<HistoricalBoard>,
+        move_builder: Signal<MoveBuilder>,
+        puzzle: Signal<Option<Puzzle>>,
+        premoves: Signal<Premoves>,
+    }
+
+    impl Peer {
+        fn new(transport: LoopbackTransport, pgn: &str) -> Self {
+            let peer = Self {
+                sync: BoardSync::new(transport),
+                historical_board: Signal::new(
+                    HistoricalBoard::from_pgn(pgn, None).expect("PGN is valid"),
+                ),
+                move_builder: Signal::new(MoveBuilder::new()),
+                puzzle: Signal::new(None),
+                premoves: Signal::new(Premoves::new()),
+            };
+            peer.publish();
+            peer
+        }
+
+        fn publish(&self) {
+            self.sync.publish(&self.historical_board.peek());
+        }
+
+        fn play(&mut self, san: &str) {
+            let m = Move::from_san(san, &self.historical_board.peek()).expect("Move is legal");
+            self.historical_board
+                .write()
+                .make_move(m)
+                .expect("Move can be made");
+            self.publish();
+        }
+
+        /// Applies the messages of the peer and publishes the resulting changes.
+        fn receive(&mut self) {
+            for message in self.sync.poll() {
+                self.sync.receive(
+                    message,
+                    &mut self.historical_board,
+                    &mut self.move_builder,
+                    &mut self.puzzle,
+                    &mut self.premoves,
+                );
+            }
+            self.publish();
+        }
+
+        fn line(&self) -> Vec<String> {
+            let board = self.historical_board.peek();
+            board.line().iter().map(Move::to_string).collect()
+        }
+    }
+
+    fn peers(pgn: &str) -> (Peer, Peer) {
+        let (one, other) = LoopbackTransport::pair();
+        (Peer::new(one, pgn), Peer::new(other, pgn))
+    }
+
+    /// Runs the `test` in the scope of a component, since signals need a Dioxus runtime.
+    fn with_runtime(test: impl FnOnce()) {
+        let mut dom = VirtualDom::new(|| rsx! {});
+        dom.rebuild_in_place();
+        dom.runtime().on_scope(ScopeId::ROOT, test);
+    }
+
+    #[test]
+    fn moves_reach_the_peer() {
+        with_runtime(|| {
+            let (mut white, mut black) = peers("*");
+
+            white.play("e4");
+            black.receive();
+            black.play("e5");
+            white.receive();
+
+            assert_eq!(white.line(), ["e2e4", "e7e5"]);
+            assert_eq!(black.line(), ["e2e4", "e7e5"]);
+        });
+    }
+
+    #[test]
+    fn smaller_move_wins_a_conflict_and_keeps_the_clocks() {
+        with_runtime(|| {
+            let (mut one, mut other) = peers("1. e4 *");
+            let source = ManualClock::new();
+            one.historical_board.write().set_clocks(
+                Some(TimeControl::sudden_death(Duration::from_secs(60))),
+                source.clone().into(),
+            );
+
+            source.advance(Duration::from_secs(10));
+            one.play("e5");
+            other.play("c5");
+            one.receive();
+            other.receive();
+
+            assert_eq!(one.line(), ["e2e4", "c7c5"]);
+            assert_eq!(other.line(), ["e2e4", "c7c5"]);
+
+            let board = one.historical_board.peek();
+            assert_eq!(board.running_clock(), Some(Color::White));
+            assert_eq!(
+                board.remaining_time(Color::Black),
+                Some(Duration::from_secs(50))
+            );
+        });
+    }
+
+    #[test]
+    fn new_game_is_resynced() {
+        with_runtime(|| {
+            let (mut one, mut other) = peers("1. e4 e5 *");
+
+            let board = HistoricalBoard::from_pgn("1. d4 d5 2. c4 *", None).expect("PGN is valid");
+            one.historical_board.set(board);
+            one.publish();
+            other.receive();
+
+            assert_eq!(other.line(), ["d2d4", "d7d5", "c2c4"]);
+
+            // Nothing is sent back, the games stay shared.
+            one.receive();
+            assert_eq!(one.line(), ["d2d4", "d7d5", "c2c4"]);
+        });
+    }
+
+    #[test]
+    fn resignation_reaches_the_peer() {
+        with_runtime(|| {
+            let (mut white, mut black) = peers("1. e4 *");
+
+            black.historical_board.write().resign(Color::Black);
+            black.publish();
+            white.receive();
+
+            assert_eq!(
+                white.historical_board.peek().agreement(),
+                Some((GameStatus::White, Termination::Resignation))
+            );
+        });
+    }
+
+    #[test]
+    fn draw_is_offered_and_accepted() {
+        with_runtime(|| {
+            let (mut white, mut black) = peers("1. e4 *");
+
+            black.historical_board.write().offer_draw(Color::Black);
+            black.publish();
+            white.receive();
+            assert_eq!(
+                white.historical_board.peek().draw_offer(),
+                Some(Color::Black)
+            );
+
+            white
+                .historical_board
+                .write()
+                .accept_draw()
+                .expect("Draw is offered");
+            white.publish();
+            black.receive();
+
+            assert_eq!(
+                black.historical_board.peek().agreement(),
+                Some((GameStatus::Draw, Termination::DrawAgreement))
+            );
+        });
+    }
+}
@@ -0,0 +1,99 @@
+use crate::transport::LineTransport;
+use std::cell::RefCell;
+use std::collections::VecDeque;
+use std::io;
+use std::rc::Rc;
+use tracing::warn;
+use wasm_bindgen::closure::Closure;
+use wasm_bindgen::JsCast;
+use web_sys::{MessageEvent, WebSocket};
+
+/// [`LineTransport`] over a WebSocket, e.g., to a relay server connecting the browsers of both players.
+///
+/// Messages sent before the connection is open are delivered once it opens.
+/// The connection is closed when the transport is dropped.
+pub struct WebSocketTransport {
+    socket: WebSocket,
+    /// Messages received from the peer, but not processed yet.
+    received: Rc<RefCell<VecDeque<String>>>,
+    /// Messages waiting for the connection to open.
+    unsent: Rc<RefCell<VecDeque<String>>>,
+    // Handlers must live as long as the socket.
+    _onmessage: Closure<dyn FnMut(MessageEvent)>,
+    _onopen: Closure<dyn FnMut()>,
+}
+
+impl WebSocketTransport {
+    /// Opens a connection to the WebSocket at the `url`.
+    pub fn connect(url: &str) -> io::Result<Self> {
+        let socket = WebSocket::new(url).map_err(js_error)?;
+
+        let received = Rc::new(RefCell::new(VecDeque::new()));
+        let onmessage = {
+            let received = received.clone();
+
+            Closure::<dyn FnMut(MessageEvent)>::new(move |ev: MessageEvent| {
+                match ev.data().as_string() {
+                    Some(message) => received.borrow_mut().push_back(message),
+                    None => warn!("Binary WebSocket messages are not supported"),
+                }
+            })
+        };
+        socket.set_onmessage(Some(onmessage.as_ref().unchecked_ref()));
+
+        let unsent = Rc::new(RefCell::new(VecDeque::<String>::new()));
+        let onopen = {
+            let socket = socket.clone();
+            let unsent = unsent.clone();
+
+            Closure::<dyn FnMut()>::new(move || {
+                for message in unsent.borrow_mut().drain(..) {
+                    if let Err(err) = socket.send_with_str(&message) {
+                        warn!("Message `{message}` cannot be sent: {err:?}");
+                    }
+                }
+            })
+        };
+        socket.set_onopen(Some(onopen.as_ref().unchecked_ref()));
+
+        Ok(Self {
+            socket,
+            received,
+            unsent,
+            _onmessage: onmessage,
+            _onopen: onopen,
+        })
+    }
+}
+
+impl LineTransport for WebSocketTransport {
+    fn send(&mut self, message: &str) -> io::Result<()> {
+        match self.socket.ready_state() {
+            WebSocket::CONNECTING => {
+                self.unsent.borrow_mut().push_back(message.to_string());
+                Ok(())
+            }
+            WebSocket::OPEN => self.socket.send_with_str(message).map_err(js_error),
+            _ => Err(io::Error::new(
+                io::ErrorKind::NotConnected,
+                "WebSocket is closed",
+            )),
+        }
+    }
+
+    fn try_receive(&mut self) -> Option<String> {
+        self.received.borrow_mut().pop_front()
+    }
+}
+
+impl Drop for WebSocketTransport {
+    fn drop(&mut self) {
+        self.socket.set_onmessage(None);
+        self.socket.set_onopen(None);
+        let _ = self.socket.close();
+    }
+}
+
+fn js_error(err: wasm_bindgen::JsValue) -> io::Error {
+    io::Error::other(format!("{err:?}"))
+}
@@ -0,0 +1,77 @@
+use std::fmt::Write;
+
+/// Look of a single [`crate::Chessboard`] instance.
+///
+/// Colors are CSS colors, e.g., `"#f0d9b5"` or `"rgb(181 136 99)"`.
+/// Unset fields keep the defaults of the board stylesheet,
+/// so a theme can be built with the struct update syntax:
+///
+/// ```
+/// # use dioxus_chessboard::BoardTheme;
+/// let theme = BoardTheme {
+///     light_square: Some("#f0d9b5".to_string()),
+///     dark_square: Some("#b58863".to_string()),
+///     ..Default::default()
+/// };
+/// ```
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct BoardTheme {
+    pub light_square: Option<String>,
+    pub dark_square: Option<String>,
+    /// Highlight of the selected piece.
+    pub move_source: Option<String>,
+    /// Highlight of the move leading to the current position.
+    pub last_move: Option<String>,
+    /// Glow around the king in check.
+    pub check: Option<String>,
+    /// Hints of the legal moves of the selected piece.
+    pub move_hint: Option<String>,
+    /// Highlight of the queued premoves.
+    pub premove: Option<String>,
+    /// Highlight of the move waiting for the confirmation of the host.
+    pub pending_move: Option<String>,
+    /// Coordinates drawn on the light squares, the dark square color by default.
+    pub coordinate_light: Option<String>,
+    /// Coordinates drawn on the dark squares, the light square color by default.
+    pub coordinate_dark: Option<String>,
+    /// Size of a piece relative to its square.
+    pub piece_scale: Option<f64>,
+    /// URL of an image drawn under the pieces instead of the square colors.
+    pub texture: Option<String>,
+}
+
+impl BoardTheme {
+    /// Inline style overriding the CSS variables of the board stylesheet.
+    pub(crate) fn style(&self) -> String {
+        let variables = [
+            ("--color-light", self.light_square.clone()),
+            ("--color-dark", self.dark_square.clone()),
+            ("--color-move-source", self.move_source.clone()),
+            ("--color-last-move", self.last_move.clone()),
+            ("--color-check", self.check.clone()),
+            ("--color-move-hint", self.move_hint.clone()),
+            ("--color-premove", self.premove.clone()),
+            ("--color-pending-move", self.pending_move.clone()),
+            ("--color-coordinate-light", self.coordinate_light.clone()),
+            ("--color-coordinate-dark", self.coordinate_dark.clone()),
+            (
+                "--piece-scale",
+                self.piece_scale.map(|scale| scale.to_string()),
+            ),
+            (
+                "--board-texture",
+                self.texture
+                    .as_ref()
+                    .map(|url| format!("url(\"{}\")", url.replace('"', "%22"))),
+            ),
+        ];
+
+        variables
+            .into_iter()
+            .filter_map(|(name, value)| value.map(|value| (name, value)))
+            .fold(String::new(), |mut style, (name, value)| {
+                let _ = write!(style, "{name}: {value}; ");
+                style
+            })
+    }
+}
@@ -0,0 +1,29 @@
+use futures_timer::Delay;
+use std::io;
+use std::time::Duration;
+
+/// Interval between polls of a transport.
+const POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+/// Channel exchanging lines of text with a counterpart of the board,
+/// e.g., a UCI engine or a peer sharing the game.
+///
+/// Lines are sent without the line terminator and received in the same way, whole and in order.
+/// Implement it to plug in a channel of your own, e.g., a scripted fake in tests.
+pub trait LineTransport {
+    /// Sends a line to the counterpart.
+    fn send(&mut self, line: &str) -> io::Result<()>;
+
+    /// Receives the next line of the counterpart, if any is available.
+    /// It must not block waiting for the counterpart.
+    fn try_receive(&mut self) -> Option<String>;
+}
+
+/// Runs `poll` periodically to process the lines received by a transport.
+/// Never completes, so it lives as long as the future of the component running it.
+pub(crate) async fn poll_periodically(mut poll: impl FnMut()) {
+    loop {
+        Delay::new(POLL_INTERVAL).await;
+        poll();
+    }
+}